@@ -15,10 +15,14 @@
 // along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
 
 use serde::Serialize;
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{fs, path::PathBuf};
 use structopt::StructOpt;
 use strum::IntoEnumIterator;
-use tidefi_primitives::{assets::Asset, networks::Network, AssetId, CurrencyId};
+use tidefi_primitives::{
+  assets::{Asset, Lifecycle},
+  networks::{Addresses, BitcoinNetwork, ChainIds, Enabled, Network, Schedules},
+  AssetId, CurrencyId,
+};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 #[serde(tag = "type", content = "id")]
@@ -50,19 +54,34 @@ struct IToken {
   #[serde(skip_serializing_if = "Option::is_none")]
   base_chain: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  router_address: Option<BTreeMap<String, String>>,
+  router_address: Option<Addresses>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  multisig_address: Option<BTreeMap<String, String>>,
+  multisig_address: Option<Addresses>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  asset_address: Option<BTreeMap<String, String>>,
+  asset_address: Option<Addresses>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  chain_id: Option<BTreeMap<String, u32>>,
-  enabled: BTreeMap<String, bool>,
+  chain_id: Option<ChainIds>,
+  enabled: Enabled,
+  #[serde(skip_serializing_if = "Schedules::is_empty")]
+  schedule: Schedules,
+  lifecycle: Lifecycle,
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct INetwork {
   name: String,
+  ss58_prefix: u16,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  genesis_hash: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rpc_endpoints: Option<&'static [&'static str]>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ws_endpoints: Option<&'static [&'static str]>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  explorer: Option<&'static str>,
+  bitcoin_network: BitcoinNetwork,
+  bitcoin_confirmations: u32,
 }
 
 fn f(a: Asset) -> String {
@@ -84,7 +103,9 @@ fn build_assets(output: Option<PathBuf>) {
       multisig_address: None,
       asset_address: None,
       chain_id: None,
-      enabled: BTreeMap::new(),
+      enabled: Enabled::new(),
+      schedule: asset.schedule(),
+      lifecycle: asset.lifecycle(),
     };
     if let Some(bc) = asset.base_chain() {
       token.base_chain = Some(f(bc));
@@ -112,14 +133,25 @@ fn build_assets(output: Option<PathBuf>) {
   std::fs::write(write_path, tz).expect("Unable to write file");
 }
 
-fn build_networks(output: Option<PathBuf>) {
+fn networks() -> Vec<INetwork> {
   let mut networks: Vec<INetwork> = vec![];
   for net in Network::iter() {
     networks.push(INetwork {
       name: format!("{:?}", net),
+      ss58_prefix: net.ss58_prefix(),
+      genesis_hash: net.genesis_hash().map(|hash| format!("{:?}", hash)),
+      rpc_endpoints: net.rpc_endpoints(),
+      ws_endpoints: net.ws_endpoints(),
+      explorer: net.explorer(),
+      bitcoin_network: net.bitcoin_network(),
+      bitcoin_confirmations: net.bitcoin_confirmations(),
     })
   }
-  let tz = serde_json::to_string_pretty(&networks).unwrap();
+  networks
+}
+
+fn build_networks(output: Option<PathBuf>) {
+  let tz = serde_json::to_string_pretty(&networks()).unwrap();
   let mut write_path = output.unwrap_or(
     std::env::current_exe()
       .expect("Unable to get current path")
@@ -170,3 +202,35 @@ fn main() -> color_eyre::Result<()> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_networks_json() {
+    let networks = serde_json::to_value(networks()).unwrap();
+    assert_eq!(
+      networks[0],
+      serde_json::json!({
+        "name": "Local",
+        "ss58Prefix": 42,
+        "rpcEndpoints": ["http://127.0.0.1:9933"],
+        "wsEndpoints": ["ws://127.0.0.1:9944"],
+        "explorer": "https://polkadot.js.org/apps/?rpc=ws%3A%2F%2F127.0.0.1%3A9944#/explorer/query/{}",
+        "bitcoinNetwork": "regtest",
+        "bitcoinConfirmations": 1,
+      })
+    );
+    // unpinned metadata is left out
+    assert_eq!(
+      networks[4],
+      serde_json::json!({
+        "name": "Mainnet",
+        "ss58Prefix": 7007,
+        "bitcoinNetwork": "mainnet",
+        "bitcoinConfirmations": 6,
+      })
+    );
+  }
+}
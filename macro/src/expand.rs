@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::parse::Def;
+use crate::parse::{Def, Lifecycle, NetworkAttr, SchedulePoint};
+use convert_case::{Case, Casing};
+
 pub fn expand(def: Def) -> proc_macro2::TokenStream {
   let enum_name = &def.item.ident;
   let enum_vis = &def.item.vis;
@@ -114,19 +116,214 @@ pub fn expand(def: Def) -> proc_macro2::TokenStream {
     quote::quote!(pub const #symbol: AssetId = #asset_id;)
   });
 
-  let try_from_asset_id2 = try_from_asset_id.clone();
+  let from_str = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    let keys = asset.lookup_keys().into_iter().map(|(key, _)| key);
+    quote::quote!(
+      if [#(#keys),*].iter().any(|key| key.eq_ignore_ascii_case(s)) {
+        return Ok(#enum_name::#asset_id);
+      }
+    )
+  });
 
-  let all_items = def.assets.iter().map(|asset| asset.inner.ident.clone());
+  let all_infos = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    let id = &asset.id;
+    let symbol = asset.symbol.to_string();
+    let name = &asset.name;
+    let decimals = &asset.decimals;
+    let algo = &asset.algo;
+    let unit_name = match &asset.unit {
+      Some(unit) => {
+        let unit_str = unit.to_string();
+        quote::quote!(Some(#unit_str))
+      }
+      None => quote::quote!(None),
+    };
+    let prefix = match &asset.prefix {
+      Some(prefix) => quote::quote!(Some(#prefix)),
+      None => quote::quote!(None),
+    };
+    let pot = asset.pot;
+    let base_chain = match &asset.base_chain {
+      Some(base_chain) => quote::quote!(Some(#enum_name::#base_chain)),
+      None => quote::quote!(None),
+    };
+    let min_stake = &asset.min_stake;
+    let max_stake = &asset.max_stake;
+    let aliases = &asset.aliases;
+    let denominations = asset
+      .denomination_table()
+      .into_iter()
+      .map(|(name, exponent)| quote::quote!(Denomination { name: #name, exponent: #exponent }));
+    let lifecycle = match &asset.lifecycle {
+      Lifecycle::Listed => quote::quote!(Lifecycle::Listed),
+      Lifecycle::Deprecated(Some(since)) => {
+        quote::quote!(Lifecycle::Deprecated { since: Some(#since) })
+      }
+      Lifecycle::Deprecated(None) => quote::quote!(Lifecycle::Deprecated { since: None }),
+      Lifecycle::Delisted => quote::quote!(Lifecycle::Delisted),
+    };
+    quote::quote!(
+      AssetInfo {
+        asset: #enum_name::#asset_id,
+        id: #id,
+        symbol: #symbol,
+        name: #name,
+        decimals: #decimals,
+        algo: Algo::#algo,
+        unit_name: #unit_name,
+        prefix: #prefix,
+        pot: #pot,
+        base_chain: #base_chain,
+        min_stake: #min_stake,
+        max_stake: #max_stake,
+        aliases: &[#(#aliases),*],
+        lifecycle: #lifecycle,
+        denominations: &[#(#denominations),*],
+      },
+    )
+  });
+
+  let info_indexes = def.assets.iter().enumerate().map(|(index, asset)| {
+    let asset_id = &asset.inner.ident;
+    quote::quote!(#enum_name::#asset_id => &Self::ALL[#index],)
+  });
+
+  // `mainnet` -> `Network::Mainnet`
+  fn network_variant(name: &syn::Ident) -> syn::Ident {
+    let lowercase = name.to_string();
+    let variant = lowercase[..1].to_uppercase() + &lowercase[1..];
+    syn::Ident::new(&variant, name.span())
+  }
+  // per-network deployment maps, `None` when the asset declares no value on any network
+  let network_map = |value: fn(&NetworkAttr) -> Option<proc_macro2::TokenStream>| {
+    def
+      .assets
+      .iter()
+      .map(|asset| {
+        let asset_id = &asset.inner.ident;
+        let entries = asset
+          .networks
+          .iter()
+          .filter_map(|network| {
+            let variant = network_variant(&network.name);
+            value(network).map(|value| quote::quote!((Network::#variant, #value)))
+          })
+          .collect::<Vec<_>>();
+        if entries.is_empty() {
+          quote::quote!(#enum_name::#asset_id => None,)
+        } else {
+          quote::quote!(#enum_name::#asset_id => Some(BTreeMap::from([#(#entries),*])),)
+        }
+      })
+      .collect::<Vec<_>>()
+  };
+  // addresses have been validated with their EIP-55 checksum
+  fn evm_address(address: &str) -> proc_macro2::TokenStream {
+    let bytes = crate::parse::evm_address(address).expect("validated EVM address");
+    quote::quote!(EvmAddress::new([#(#bytes),*]))
+  }
+  let chain_ids = network_map(|network| network.chain_id.map(|chain_id| quote::quote!(#chain_id)));
+  let routers = network_map(|network| network.router.as_deref().map(evm_address));
+  let multisigs = network_map(|network| network.multisig.as_deref().map(evm_address));
+  let addresses = network_map(|network| network.address.as_deref().map(evm_address));
+
+  let enabled = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    let entries = asset.networks.iter().map(|network| {
+      let variant = network_variant(&network.name);
+      let enabled = network.enabled;
+      quote::quote!((Network::#variant, #enabled))
+    });
+    quote::quote!(#enum_name::#asset_id => BTreeMap::from([#(#entries),*]),)
+  });
+
+  let schedule_point = |point: Option<SchedulePoint>| match point {
+    Some(SchedulePoint::Block(block)) => quote::quote!(Some(SchedulePoint::Block(#block))),
+    Some(SchedulePoint::Timestamp(timestamp)) => {
+      quote::quote!(Some(SchedulePoint::Timestamp(#timestamp)))
+    }
+    None => quote::quote!(None),
+  };
+  let schedules = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    let entries = asset
+      .networks
+      .iter()
+      .filter(|network| network.enabled_from.is_some() || network.disabled_at.is_some())
+      .map(|network| {
+        let variant = network_variant(&network.name);
+        let enabled_from = schedule_point(network.enabled_from);
+        let disabled_at = schedule_point(network.disabled_at);
+        quote::quote!((
+          Network::#variant,
+          Schedule { enabled_from: #enabled_from, disabled_at: #disabled_at },
+        ))
+      });
+    quote::quote!(#enum_name::#asset_id => BTreeMap::from([#(#entries),*]),)
+  });
+
+  let all_symbols = def.assets.iter().map(|asset| asset.symbol.to_string());
+
+  let native = &def.native().inner.ident;
+  let native_symbol = &def.native().symbol;
+
+  // the native asset is only represented by `CurrencyId::Tdfy`, never wrapped
+  let try_from_wrapped = def
+    .assets
+    .iter()
+    .filter(|asset| asset.native.is_none())
+    .map(|asset| {
+      let symbol = &asset.symbol;
+      let asset_id = &asset.inner.ident;
+      quote::quote!(#symbol => Ok(#enum_name::#asset_id),)
+    });
+
+  let round_trips = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    let msg = format!("`{}` doesn't round-trip through `CurrencyId`", asset_id);
+    quote::quote!(
+      assert!(
+        #enum_name::try_from(#enum_name::#asset_id.currency_id()) == Ok(#enum_name::#asset_id),
+        #msg
+      );
+    )
+  });
+  let round_trip_test = quote::format_ident!(
+    "{}_currency_id_round_trip",
+    enum_name.to_string().to_case(Case::Snake)
+  );
+
+  let all_items = def.assets.iter().map(|asset| {
+    let asset_id = &asset.inner.ident;
+    match &asset.lifecycle {
+      Lifecycle::Listed => quote::quote!(#asset_id),
+      Lifecycle::Deprecated(Some(since)) => quote::quote!(#[deprecated(since = #since)] #asset_id),
+      Lifecycle::Deprecated(None) => quote::quote!(#[deprecated] #asset_id),
+      Lifecycle::Delisted => quote::quote!(#[deprecated(note = "delisted asset")] #asset_id),
+    }
+  });
+
+  // rebuild when the manifest changes
+  let manifest = def.manifest.as_ref().map(|path| {
+    quote::quote!(
+      const _: &[u8] = include_bytes!(#path);
+    )
+  });
 
   quote::quote!(
+    #manifest
+
     #(#all_consts)*
 
     #[derive(Clone, PartialEq, Eq)]
-    #[cfg_attr(feature = "std", derive(EnumIter, Debug, Serialize, Deserialize, Hash))]
+    #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize, Hash))]
     #enum_vis enum #enum_name {
       #(#all_items),*
     }
 
+    #[allow(deprecated)]
     impl TryFrom<AssetId> for #enum_name {
       type Error = &'static str;
       fn try_from(asset: AssetId) -> Result<#enum_name, Self::Error> {
@@ -137,20 +334,248 @@ pub fn expand(def: Def) -> proc_macro2::TokenStream {
       }
     }
 
-    impl TryFrom<CurrencyId> for Asset {
-      type Error = &'static str;
+    #[allow(deprecated)]
+    impl TryFrom<CurrencyId> for #enum_name {
+      type Error = CurrencyIdError;
       fn try_from(currency: CurrencyId) -> Result<#enum_name, Self::Error> {
         match currency {
-          CurrencyId::Tdfy => Ok(#enum_name::Tdfy),
+          CurrencyId::Tdfy => Ok(#enum_name::#native),
           CurrencyId::Wrapped(asset) => match asset {
-            #(#try_from_asset_id2)*
-            _ => Err("Invalid asset"),
+            #(#try_from_wrapped)*
+            #native_symbol => Err(CurrencyIdError::WrappedNativeAsset(asset)),
+            _ => Err(CurrencyIdError::UnknownAsset(asset)),
           },
         }
       }
     }
 
-    impl Asset {
+    #[cfg(test)]
+    #[test]
+    #[allow(deprecated)]
+    fn #round_trip_test() {
+      #(#round_trips)*
+      assert!(
+        #enum_name::try_from(CurrencyId::Wrapped(#native_symbol))
+          == Err(CurrencyIdError::WrappedNativeAsset(#native_symbol)),
+        "the native asset can't be wrapped"
+      );
+    }
+
+    // SCALE encode the asset as its declared `AssetId`, not as the variant index
+    #[allow(deprecated)]
+    impl codec::Encode for #enum_name {
+      fn size_hint(&self) -> usize {
+        codec::Encode::size_hint(&self.id())
+      }
+
+      fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+        codec::Encode::encode_to(&self.id(), dest)
+      }
+    }
+
+    #[allow(deprecated)]
+    impl codec::EncodeLike for #enum_name {}
+
+    #[allow(deprecated)]
+    impl codec::Decode for #enum_name {
+      fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let asset_id = <AssetId as codec::Decode>::decode(input)?;
+        #enum_name::try_from(asset_id).map_err(|_| "Invalid asset id".into())
+      }
+    }
+
+    #[allow(deprecated)]
+    impl codec::MaxEncodedLen for #enum_name {
+      fn max_encoded_len() -> usize {
+        <AssetId as codec::MaxEncodedLen>::max_encoded_len()
+      }
+    }
+
+    #[allow(deprecated)]
+    impl scale_info::TypeInfo for #enum_name {
+      type Identity = Self;
+      fn type_info() -> scale_info::Type {
+        scale_info::Type::builder()
+          .path(scale_info::Path::new(stringify!(#enum_name), module_path!()))
+          .composite(
+            scale_info::build::Fields::unnamed().field(|f| f.ty::<AssetId>().type_name("AssetId")),
+          )
+      }
+    }
+
+    #[allow(deprecated)]
+    impl core::str::FromStr for #enum_name {
+      type Err = ParseAssetError;
+      /// Parse an asset from its symbol, name or one of its aliases, ignoring the case.
+      fn from_str(s: &str) -> Result<#enum_name, Self::Err> {
+        #(#from_str)*
+        Err(ParseAssetError {
+          input: s.to_string(),
+          valid_symbols: &[#(#all_symbols),*],
+        })
+      }
+    }
+
+    #[allow(deprecated)]
+    impl TryFrom<&str> for #enum_name {
+      type Error = ParseAssetError;
+      fn try_from(s: &str) -> Result<#enum_name, Self::Error> {
+        s.parse()
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #enum_name {
+      /// Metadata of every declared asset, in declaration order.
+      pub const ALL: &'static [AssetInfo<#enum_name>] = &[
+        #(#all_infos)*
+      ];
+
+      /// Return the metadata of the asset.
+      pub const fn info(&self) -> &'static AssetInfo<#enum_name> {
+        match self {
+          #(#info_indexes)*
+        }
+      }
+
+      /// Return the lifecycle of the asset, delisted assets are kept to decode historical data.
+      pub const fn lifecycle(&self) -> Lifecycle {
+        self.info().lifecycle
+      }
+
+      /// Iterate over the assets which are not delisted, in declaration order.
+      pub fn iter_active() -> impl Iterator<Item = #enum_name> {
+        Self::ALL
+          .iter()
+          .filter(|info| info.lifecycle.is_active())
+          .map(|info| info.asset.clone())
+      }
+
+      /// Find the metadata of an asset by its `AssetId`.
+      pub const fn info_by_id(id: AssetId) -> Option<&'static AssetInfo<#enum_name>> {
+        let mut index = 0;
+        while index < Self::ALL.len() {
+          if Self::ALL[index].id == id {
+            return Some(&Self::ALL[index]);
+          }
+          index += 1;
+        }
+        None
+      }
+
+      /// Find the metadata of an asset by its symbol, ignoring the case.
+      pub const fn info_by_symbol(symbol: &str) -> Option<&'static AssetInfo<#enum_name>> {
+        let mut index = 0;
+        while index < Self::ALL.len() {
+          if Self::ALL[index].is_symbol(symbol) {
+            return Some(&Self::ALL[index]);
+          }
+          index += 1;
+        }
+        None
+      }
+    }
+
+    // implemented from the metadata table, as `strum`'s derive warns on deprecated variants
+    #[cfg(feature = "std")]
+    #[allow(deprecated)]
+    impl strum::IntoEnumIterator for #enum_name {
+      type Iterator = core::iter::Map<
+        core::slice::Iter<'static, AssetInfo<#enum_name>>,
+        fn(&'static AssetInfo<#enum_name>) -> #enum_name,
+      >;
+      fn iter() -> Self::Iterator {
+        Self::ALL.iter().map(|info| info.asset.clone())
+      }
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(deprecated)]
+    impl #enum_name {
+      /// Return the EVM chain id of the asset on each network.
+      pub fn chain_id(&self) -> Option<ChainIds> {
+        match self {
+          #(#chain_ids)*
+        }
+      }
+
+      /// Return the router contract address of the asset on each network.
+      pub fn router(&self) -> Option<Addresses> {
+        match self {
+          #(#routers)*
+        }
+      }
+
+      /// Return the multisig wallet address of the asset on each network.
+      pub fn multisig(&self) -> Option<Addresses> {
+        match self {
+          #(#multisigs)*
+        }
+      }
+
+      /// Return the token contract address of the asset on each network.
+      pub fn address(&self) -> Option<Addresses> {
+        match self {
+          #(#addresses)*
+        }
+      }
+
+      /// Return whether the asset is enabled on each declared network.
+      pub fn enabled(&self) -> Enabled {
+        match self {
+          #(#enabled)*
+        }
+      }
+
+      /// Return when the asset is listed or delisted on each network with a schedule.
+      pub fn schedule(&self) -> Schedules {
+        match self {
+          #(#schedules)*
+        }
+      }
+
+      /// Return whether the asset is enabled on the network at a block or a timestamp, or
+      /// `None` when its schedule is bounded by the other kind of point, see
+      /// `Schedule::is_active_at`. Delisted assets are never enabled.
+      pub fn is_enabled_at(&self, network: &Network, at: SchedulePoint) -> Option<bool> {
+        if !self.lifecycle().is_active() || !self.enabled_on(network) {
+          return Some(false);
+        }
+        self.schedule().remove(network).unwrap_or_default().is_active_at(at)
+      }
+
+      /// Return the EVM chain id of the asset on the network.
+      ///
+      /// The `*_on` lookups only read the tables declared on the registry, they return
+      /// `None` or `false` for a `Network::Custom` whose deployments are only known to a
+      /// `NetworkRegistry`.
+      pub fn chain_id_on(&self, network: &Network) -> Option<u32> {
+        self.chain_id()?.remove(network)
+      }
+
+      /// Return the router contract address of the asset on the network.
+      pub fn router_on(&self, network: &Network) -> Option<EvmAddress> {
+        self.router()?.remove(network)
+      }
+
+      /// Return the multisig wallet address of the asset on the network.
+      pub fn multisig_on(&self, network: &Network) -> Option<EvmAddress> {
+        self.multisig()?.remove(network)
+      }
+
+      /// Return the token contract address of the asset on the network.
+      pub fn address_on(&self, network: &Network) -> Option<EvmAddress> {
+        self.address()?.remove(network)
+      }
+
+      /// Return whether the asset is enabled on the network, `false` when it isn't declared.
+      pub fn enabled_on(&self, network: &Network) -> bool {
+        self.enabled().remove(network).unwrap_or_default()
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #enum_name {
       /// Get the `AssetId` used on-chain with the `pallet_assets`
       pub fn id(&self) -> AssetId {
         match self {
@@ -160,7 +585,7 @@ pub fn expand(def: Def) -> proc_macro2::TokenStream {
 
       /// Return the `CurrencyId` used by different pallets for Tidechain
       pub fn currency_id(&self) -> CurrencyId {
-        if self == &Asset::Tdfy {
+        if self == &#enum_name::#native {
           return CurrencyId::Tdfy;
         }
         CurrencyId::Wrapped(self.id())
@@ -209,7 +634,7 @@ pub fn expand(def: Def) -> proc_macro2::TokenStream {
       }
 
       /// Based chain connected to the asset. (mainly used to identify wrapped tokens)
-      pub fn base_chain(&self) -> Option<Asset> {
+      pub fn base_chain(&self) -> Option<#enum_name> {
         match self {
           #(#base_chains)*
         }
@@ -241,6 +666,54 @@ pub fn expand(def: Def) -> proc_macro2::TokenStream {
       pub fn saturating_mul(&self, amount: Balance) -> Balance {
         amount.saturating_mul(10_u128.pow(self.exponent() as u32))
       }
+
+      /// Checked integer multiplication, fails instead of saturating at the numeric bounds.
+      /// By example, if you use `Asset::Bitcoin.checked_mul(10)` it'll return `Ok(1_000_000_000)`
+      pub fn checked_mul(&self, amount: Balance) -> Result<Balance, ArithmeticOverflow> {
+        self.info().checked_mul(amount)
+      }
+
+      /// Convert an amount in base units to a fixed point number of units of the asset.
+      /// By example, `Asset::Bitcoin.checked_to_fixed(150_000_000)` returns `Ok(1.5)`
+      pub fn checked_to_fixed(&self, amount: Balance) -> Result<FixedU128, ArithmeticOverflow> {
+        self.info().checked_to_fixed(amount)
+      }
+
+      /// Convert a fixed point number of units of the asset to base units.
+      /// By example, `Asset::Bitcoin.checked_from_fixed(1.5)` returns `Ok(150_000_000)`
+      pub fn checked_from_fixed(&self, value: FixedU128) -> Result<Balance, ArithmeticOverflow> {
+        self.info().checked_from_fixed(value)
+      }
+
+      /// Parse a decimal amount of the asset into base units.
+      /// By example, `Asset::Bitcoin.parse_amount("0.015 BTC")` returns `Ok(1_500_000)`
+      pub fn parse_amount(&self, amount: &str) -> Result<Balance, AmountError> {
+        self.info().parse_amount(amount)
+      }
+
+      /// Convert an amount in base units to the decimals of `other`, with the dust lost by rounding.
+      /// By example, `Asset::Ethereum.rescale_to(&Asset::Tether, 1_500_000_000_000, Rounding::Floor)`
+      /// returns `Some((1, 500_000_000_000))`
+      pub fn rescale_to(
+        &self,
+        other: &#enum_name,
+        amount: Balance,
+        rounding: Rounding,
+      ) -> Option<(Balance, Amount)> {
+        self.info().rescale_to(other.info(), amount, rounding)
+      }
+
+      /// Convert an amount between two denominations of the asset.
+      /// By example, `Asset::Ethereum.convert(2, "gwei", "wei")` returns `Ok(2_000_000_000)`
+      pub fn convert(&self, amount: Balance, from: &str, to: &str) -> Result<Balance, DenominationError> {
+        self.info().convert(amount, from, to)
+      }
+
+      /// Format an amount in base units as a decimal amount of the asset.
+      /// By example, `Asset::Bitcoin.format_amount(1_500_000, Default::default())` returns `0.015`
+      pub fn format_amount(&self, amount: Balance, options: FormatOptions) -> String {
+        self.info().format_amount(amount, options)
+      }
     }
   )
 }
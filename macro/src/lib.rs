@@ -18,21 +18,41 @@ use proc_macro::{self, TokenStream};
 use syn::spanned::Spanned;
 
 mod expand;
+mod manifest;
 mod parse;
 mod utils;
 
 #[proc_macro_attribute]
 pub fn assets(attr: TokenStream, item: TokenStream) -> TokenStream {
-  if !attr.is_empty() {
-    let msg = "Invalid assets macro call: expected no attributes, e.g. macro call must be just \
-			``#[assets]`";
-    let span = proc_macro2::TokenStream::from(attr).span();
-    return syn::Error::new(span, msg).to_compile_error().into();
-  }
+  let attr = match syn::parse::<parse::AssetsAttr>(attr.clone()) {
+    Ok(attr) => attr,
+    Err(_) => {
+      let msg = "Invalid assets macro call: expected no attributes or a manifest, e.g. macro call \
+        must be just `#[assets]` or `#[assets(file = \"assets.toml\")]`";
+      let span = proc_macro2::TokenStream::from(attr).span();
+      return syn::Error::new(span, msg).to_compile_error().into();
+    }
+  };
 
   let item = syn::parse_macro_input!(item as syn::ItemEnum);
-  match parse::Def::try_from(item) {
+  match parse::Def::try_from(item, attr) {
     Ok(def) => expand::expand(def).into(),
     Err(e) => e.to_compile_error().into(),
   }
 }
+
+/// Networks accepted by `#[asset::network(..)]`, checked against `Network` by the primitives tests.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __networks(_item: TokenStream) -> TokenStream {
+  let networks = parse::NETWORKS;
+  quote::quote!(&[#(#networks),*]).into()
+}
+
+/// Algos accepted by `#[asset::algo]`, checked against `Algo` by the primitives tests.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __algos(_item: TokenStream) -> TokenStream {
+  let algos = parse::ALGOS;
+  quote::quote!(&[#(#algos),*]).into()
+}
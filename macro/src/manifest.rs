@@ -0,0 +1,607 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Load the assets of `#[assets(file = "assets.toml")]` from a TOML or JSON manifest, e.g.:
+//!
+//! ```toml
+//! [[asset]]
+//! variant = "Bitcoin"
+//! id = 2
+//! symbol = "BTC"
+//! name = "Bitcoin"
+//! decimals = 8
+//! algo = "SECP256K1"
+//! unit = "satoshi"
+//! prefix = "₿"
+//! pot = true
+//! aliases = ["XBT"]
+//! min_stake = 100
+//! max_stake = "5"
+//!
+//! [[asset.network]]
+//! name = "mainnet"
+//! enabled = true
+//! disabled_at = { block = 15_000_000 }
+//!
+//! [[asset.denomination]]
+//! name = "mBTC"
+//! exponent = 5
+//! ```
+//!
+//! Keys mirror the `#[asset::*]` attributes. As with attributes, integer amounts are in base units
+//! and string amounts in units of the asset, scaled by its decimals, and `native`, `status` and
+//! `since` replace `#[asset::native]`, `#[asset::status = "…"]` and
+//! `#[asset::deprecated(since = "…")]`.
+//!
+//! The format is picked from the extension: `.toml` or `.json`, where the manifest is an object
+//! with the same `asset` array, other extensions are rejected. Only TOML tracks where values
+//! are, errors in JSON manifests are reported on the file and the variant of the asset.
+
+use crate::parse::{Amount, Asset, DenominationAttr, Lifecycle, NetworkAttr, SchedulePoint};
+use serde::{Deserialize, Deserializer};
+use std::{cell::Cell, path::PathBuf};
+
+thread_local! {
+  /// Whether the manifest being deserialized is TOML, the only format tracking value offsets.
+  static TRACK_OFFSETS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A manifest value with its byte offset in TOML manifests.
+struct Spanned<T> {
+  start: Option<usize>,
+  value: T,
+}
+
+impl<T> Spanned<T> {
+  fn start(&self) -> Option<usize> {
+    self.start
+  }
+
+  fn get_ref(&self) -> &T {
+    &self.value
+  }
+
+  fn into_inner(self) -> T {
+    self.value
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Spanned<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    if TRACK_OFFSETS.with(Cell::get) {
+      let spanned = toml::Spanned::<T>::deserialize(deserializer)?;
+      Ok(Spanned {
+        start: Some(spanned.start()),
+        value: spanned.into_inner(),
+      })
+    } else {
+      T::deserialize(deserializer).map(|value| Spanned { start: None, value })
+    }
+  }
+}
+
+/// Manifest formats, by extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+  Toml,
+  Json,
+}
+
+impl Format {
+  fn from_extension(extension: &str) -> Option<Self> {
+    match extension {
+      "toml" => Some(Format::Toml),
+      "json" => Some(Format::Json),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+  #[serde(default)]
+  asset: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+  variant: Spanned<String>,
+  id: Spanned<u32>,
+  symbol: Spanned<String>,
+  name: Spanned<String>,
+  decimals: Spanned<u8>,
+  algo: Spanned<String>,
+  unit: Option<Spanned<String>>,
+  prefix: Option<Spanned<String>>,
+  pot: Option<Spanned<bool>>,
+  base_chain: Option<Spanned<String>>,
+  min_stake: Spanned<AmountEntry>,
+  max_stake: Spanned<AmountEntry>,
+  #[serde(default)]
+  aliases: Vec<Spanned<String>>,
+  native: Option<Spanned<bool>>,
+  status: Option<Spanned<String>>,
+  since: Option<Spanned<String>>,
+  #[serde(default)]
+  network: Vec<NetworkEntry>,
+  #[serde(default)]
+  denomination: Vec<DenominationEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NetworkEntry {
+  name: Spanned<String>,
+  address: Option<String>,
+  router: Option<String>,
+  multisig: Option<String>,
+  chain_id: Option<u32>,
+  enabled: Option<bool>,
+  enabled_from: Option<SchedulePoint>,
+  disabled_at: Option<SchedulePoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DenominationEntry {
+  name: Spanned<String>,
+  exponent: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AmountEntry {
+  Integer(u64),
+  Units(String),
+}
+
+/// Location of an asset loaded from a manifest, used to report errors on the right line.
+#[derive(Debug)]
+pub struct Origin {
+  file: String,
+  variant: String,
+  entry: Option<(usize, usize)>,
+  keys: Vec<(String, (usize, usize))>,
+}
+
+impl Origin {
+  /// `file:line:column` of `key` in the manifest, or of the asset entry when unknown.
+  /// Without positions, e.g. in JSON manifests, `file` and the variant of the asset.
+  pub fn position(&self, key: &str) -> String {
+    match self
+      .keys
+      .iter()
+      .find(|(found, _)| found == key)
+      .map(|(_, position)| *position)
+      .or(self.entry)
+    {
+      Some((line, column)) => format!("{}:{}:{}", self.file, line, column),
+      None => format!("{}: `{}`", self.file, self.variant),
+    }
+  }
+}
+
+struct Loader<'a> {
+  file: String,
+  content: &'a str,
+  span: proc_macro2::Span,
+}
+
+impl<'a> Loader<'a> {
+  /// One-based line and column of a byte offset.
+  fn position(&self, offset: usize) -> (usize, usize) {
+    let before = &self.content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+    (line, column)
+  }
+
+  fn error<T>(&self, value: &Spanned<T>, msg: String) -> syn::Error {
+    let location = match value.start() {
+      Some(offset) => {
+        let (line, column) = self.position(offset);
+        format!("{}:{}:{}", self.file, line, column)
+      }
+      None => self.file.clone(),
+    };
+    syn::Error::new(self.span, format!("{}: {}", location, msg))
+  }
+
+  fn ident(&self, value: &Spanned<String>) -> syn::Result<syn::Ident> {
+    let mut ident = syn::parse_str::<syn::Ident>(value.get_ref()).map_err(|_| {
+      self.error(
+        value,
+        format!("`{}` is not a valid identifier", value.get_ref()),
+      )
+    })?;
+    ident.set_span(self.span);
+    Ok(ident)
+  }
+
+  fn amount(&self, value: &Spanned<AmountEntry>, decimals: u8) -> syn::Result<u128> {
+    let amount = match value.get_ref() {
+      AmountEntry::Integer(amount) => Amount::Base((*amount).into()),
+      AmountEntry::Units(units) => Amount::Units(units.clone()),
+    };
+    amount
+      .to_base(decimals)
+      .map_err(|msg| self.error(value, msg))
+  }
+
+  fn asset(&self, entry: Entry) -> syn::Result<Asset> {
+    let mut keys = vec![
+      ("id", entry.id.start()),
+      ("symbol", entry.symbol.start()),
+      ("name", entry.name.start()),
+      ("decimals", entry.decimals.start()),
+      ("algo", entry.algo.start()),
+      ("min_stake", entry.min_stake.start()),
+      ("max_stake", entry.max_stake.start()),
+    ]
+    .into_iter()
+    .filter_map(|(key, offset)| Some((key.to_string(), self.position(offset?))))
+    .collect::<Vec<_>>();
+    let mut position = |key: String, offset: Option<usize>| {
+      if let Some(offset) = offset {
+        keys.push((key, self.position(offset)));
+      }
+    };
+
+    let variant = self.ident(&entry.variant)?;
+    let base_chain = match &entry.base_chain {
+      Some(base_chain) => {
+        position("base_chain".into(), base_chain.start());
+        Some(self.ident(base_chain)?)
+      }
+      None => None,
+    };
+    if let Some(alias) = entry.aliases.first() {
+      position("aliases".into(), alias.start());
+    }
+    let native = match &entry.native {
+      Some(native) if *native.get_ref() => {
+        position("native".into(), native.start());
+        Some(self.span)
+      }
+      _ => None,
+    };
+
+    let lifecycle = match (&entry.status, &entry.since) {
+      (Some(status), since) => match Lifecycle::from_status(status.get_ref()) {
+        Ok(Lifecycle::Deprecated(_)) => {
+          Lifecycle::Deprecated(since.as_ref().map(|since| since.get_ref().clone()))
+        }
+        Ok(_) if since.is_some() => {
+          return Err(self.error(status, "`since` is only valid for deprecated assets".into()))
+        }
+        Ok(lifecycle) => lifecycle,
+        Err(msg) => return Err(self.error(status, msg)),
+      },
+      (None, Some(since)) => {
+        return Err(self.error(since, "`since` requires `status = \"deprecated\"`".into()))
+      }
+      (None, None) => Lifecycle::Listed,
+    };
+
+    let mut networks = Vec::new();
+    for network in entry.network {
+      position(
+        format!("network.{}", network.name.get_ref()),
+        network.name.start(),
+      );
+      networks.push(NetworkAttr {
+        name: self.ident(&network.name)?,
+        address: network.address,
+        router: network.router,
+        multisig: network.multisig,
+        chain_id: network.chain_id,
+        enabled: network.enabled.unwrap_or(true),
+        enabled_from: network.enabled_from,
+        disabled_at: network.disabled_at,
+      });
+    }
+
+    let mut denominations = Vec::new();
+    for denomination in entry.denomination {
+      position(
+        format!("denomination.{}", denomination.name.get_ref()),
+        denomination.name.start(),
+      );
+      denominations.push(DenominationAttr {
+        name: self.ident(&denomination.name)?,
+        exponent: denomination.exponent,
+      });
+    }
+
+    Ok(Asset {
+      id: *entry.id.get_ref(),
+      id_span: self.span,
+      inner: syn::Variant {
+        attrs: Vec::new(),
+        ident: variant,
+        fields: syn::Fields::Unit,
+        discriminant: None,
+      },
+      symbol: self.ident(&entry.symbol)?,
+      name: entry.name.get_ref().clone(),
+      name_span: self.span,
+      decimals: *entry.decimals.get_ref(),
+      decimals_span: self.span,
+      algo: self.ident(&entry.algo)?,
+      unit: entry
+        .unit
+        .as_ref()
+        .map(|unit| self.ident(unit))
+        .transpose()?,
+      prefix: entry.prefix.map(|prefix| prefix.into_inner()),
+      pot: entry.pot.map(|pot| pot.into_inner()).unwrap_or_default(),
+      base_chain,
+      min_stake: self.amount(&entry.min_stake, *entry.decimals.get_ref())?,
+      min_stake_span: self.span,
+      max_stake: self.amount(&entry.max_stake, *entry.decimals.get_ref())?,
+      aliases: entry
+        .aliases
+        .iter()
+        .map(|alias| syn::LitStr::new(alias.get_ref(), self.span))
+        .collect(),
+      networks,
+      native,
+      lifecycle,
+      denominations,
+      origin: Some(Origin {
+        file: self.file.clone(),
+        variant: entry.variant.get_ref().clone(),
+        entry: entry.variant.start().map(|offset| self.position(offset)),
+        keys,
+      }),
+    })
+  }
+}
+
+/// Load the assets declared in `file`, relative to the crate root.
+/// Returns the absolute path of the manifest, so the expansion can track its changes.
+pub fn load(file: &syn::LitStr) -> syn::Result<(String, Vec<Asset>)> {
+  let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+  let path = PathBuf::from(root).join(file.value());
+  let format = match path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .and_then(Format::from_extension)
+  {
+    Some(format) => format,
+    None => {
+      let msg = format!(
+        "Unsupported manifest `{}`, expected a `.toml` or `.json` file",
+        file.value()
+      );
+      return Err(syn::Error::new(file.span(), msg));
+    }
+  };
+  let content = std::fs::read_to_string(&path).map_err(|e| {
+    let msg = format!("Unable to read `{}`: {}", path.display(), e);
+    syn::Error::new(file.span(), msg)
+  })?;
+
+  let assets = parse(&file.value(), format, &content, file.span())?;
+
+  Ok((path.display().to_string(), assets))
+}
+
+/// Parse the manifest `content`, errors are reported on `span` with their position in `file`.
+fn parse(
+  file: &str,
+  format: Format,
+  content: &str,
+  span: proc_macro2::Span,
+) -> syn::Result<Vec<Asset>> {
+  let loader = Loader {
+    file: file.to_string(),
+    content,
+    span,
+  };
+  let manifest = match format {
+    Format::Toml => {
+      TRACK_OFFSETS.with(|track| track.set(true));
+      let manifest = toml::from_str::<Manifest>(content);
+      TRACK_OFFSETS.with(|track| track.set(false));
+      manifest.map_err(|e| {
+        let (line, column) = e
+          .line_col()
+          .map_or((1, 1), |(line, column)| (line + 1, column + 1));
+        (line, column, e.to_string())
+      })
+    }
+    Format::Json => {
+      serde_json::from_str::<Manifest>(content).map_err(|e| (e.line(), e.column(), e.to_string()))
+    }
+  }
+  .map_err(|(line, column, e)| {
+    syn::Error::new(span, format!("{}:{}:{}: {}", file, line, column, e))
+  })?;
+
+  manifest
+    .asset
+    .into_iter()
+    .map(|entry| loader.asset(entry))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BITCOIN: &str = r#"
+[[asset]]
+variant = "Bitcoin"
+id = 2
+symbol = "BTC"
+name = "Bitcoin"
+decimals = 8
+algo = "SECP256K1"
+aliases = ["XBT"]
+min_stake = 100
+max_stake = "5_000_000_000_000.5"
+
+[[asset.network]]
+name = "mainnet"
+enabled = false
+
+[[asset.denomination]]
+name = "mBTC"
+exponent = 5
+"#;
+
+  const BITCOIN_JSON: &str = r#"{
+  "asset": [
+    {
+      "variant": "Bitcoin",
+      "id": 2,
+      "symbol": "BTC",
+      "name": "Bitcoin",
+      "decimals": 8,
+      "algo": "SECP256K1",
+      "aliases": ["XBT"],
+      "min_stake": 100,
+      "max_stake": "5_000_000_000_000.5",
+      "network": [{ "name": "mainnet", "enabled": false, "disabled_at": { "block": 42 } }],
+      "denomination": [{ "name": "mBTC", "exponent": 5 }]
+    }
+  ]
+}"#;
+
+  fn parse_err(content: &str) -> String {
+    parse(
+      "assets.toml",
+      Format::Toml,
+      content,
+      proc_macro2::Span::call_site(),
+    )
+    .map(|_| ())
+    .expect_err("manifest should be invalid")
+    .to_string()
+  }
+
+  #[test]
+  fn test_load_assets() {
+    let assets = parse(
+      "assets.toml",
+      Format::Toml,
+      BITCOIN,
+      proc_macro2::Span::call_site(),
+    )
+    .expect("manifest is valid");
+    assert_eq!(assets.len(), 1);
+
+    let bitcoin = &assets[0];
+    assert_eq!(bitcoin.inner.ident, "Bitcoin");
+    assert_eq!(bitcoin.id, 2);
+    assert_eq!(bitcoin.symbol, "BTC");
+    assert_eq!(bitcoin.max_stake, 500_000_000_000_050_000_000);
+    assert_eq!(bitcoin.aliases[0].value(), "XBT");
+    assert_eq!(bitcoin.networks[0].name, "mainnet");
+    assert!(!bitcoin.networks[0].enabled);
+    assert_eq!(bitcoin.lifecycle, Lifecycle::Listed);
+    assert_eq!(bitcoin.denominations[0].name, "mBTC");
+    assert_eq!(bitcoin.denominations[0].exponent, 5);
+
+    let span = proc_macro2::Span::call_site();
+    assert_eq!(
+      bitcoin.error("min_stake", span, "oops".into()).to_string(),
+      "assets.toml:10:13: oops"
+    );
+    assert_eq!(
+      bitcoin
+        .error("network.mainnet", span, "oops".into())
+        .to_string(),
+      "assets.toml:14:8: oops"
+    );
+  }
+
+  #[test]
+  fn test_load_json_assets() {
+    let span = proc_macro2::Span::call_site();
+    let assets = parse("assets.json", Format::Json, BITCOIN_JSON, span).expect("manifest is valid");
+    assert_eq!(assets.len(), 1);
+
+    let bitcoin = &assets[0];
+    assert_eq!(bitcoin.inner.ident, "Bitcoin");
+    assert_eq!(bitcoin.symbol, "BTC");
+    assert_eq!(bitcoin.max_stake, 500_000_000_000_050_000_000);
+    assert_eq!(bitcoin.aliases[0].value(), "XBT");
+    assert!(!bitcoin.networks[0].enabled);
+    assert_eq!(
+      bitcoin.networks[0].disabled_at,
+      Some(SchedulePoint::Block(42))
+    );
+    assert_eq!(bitcoin.denominations[0].name, "mBTC");
+
+    // JSON doesn't track where values are
+    assert_eq!(
+      bitcoin.error("min_stake", span, "oops".into()).to_string(),
+      "assets.json: `Bitcoin`: oops"
+    );
+    let json_err = |content: String| {
+      parse("assets.json", Format::Json, &content, span)
+        .map(|_| ())
+        .expect_err("manifest should be invalid")
+        .to_string()
+    };
+    assert_eq!(
+      json_err(BITCOIN_JSON.replace("\"BTC\"", "\"B-TC\"")),
+      "assets.json: `B-TC` is not a valid identifier"
+    );
+    assert_eq!(
+      json_err(BITCOIN_JSON.replace("\"decimals\": 8,", "\"decimals\": 8")),
+      "assets.json:9:7: expected `,` or `}` at line 9 column 7"
+    );
+  }
+
+  #[test]
+  fn test_unsupported_manifest() {
+    let file = syn::LitStr::new("assets.yaml", proc_macro2::Span::call_site());
+    assert_eq!(
+      load(&file).map(|_| ()).unwrap_err().to_string(),
+      "Unsupported manifest `assets.yaml`, expected a `.toml` or `.json` file"
+    );
+  }
+
+  #[test]
+  fn test_invalid_entries() {
+    assert!(
+      parse_err(&BITCOIN.replace("decimals = 8", "decimals = 300")).starts_with("assets.toml:7:")
+    );
+    assert_eq!(
+      parse_err(&BITCOIN.replace("min_stake = 100", "min_stake = \"1O0\"")),
+      "assets.toml:10:13: `1O0` is not a valid amount, expected a decimal number such as \"10\" or \
+       \"0.5\""
+    );
+    assert_eq!(
+      parse_err(&BITCOIN.replace("min_stake = 100", "min_stake = \"0.000000001\"")),
+      "assets.toml:10:13: `0.000000001` has more than 8 decimals and would lose precision"
+    );
+    assert_eq!(
+      parse_err(&BITCOIN.replace("symbol = \"BTC\"", "symbol = \"B-TC\"")),
+      "assets.toml:5:10: `B-TC` is not a valid identifier"
+    );
+    assert_eq!(
+      parse_err(&BITCOIN.replace("aliases", "status = \"retired\"\naliases")),
+      "assets.toml:9:10: `retired` is not a valid status, expected one of: listed, deprecated, \
+       delisted"
+    );
+  }
+}
@@ -14,11 +14,30 @@
 // You should have received a copy of the GNU General Public License
 // along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::utils;
-use syn::spanned::Spanned;
+use crate::{manifest, utils};
 
 mod keyword {
   syn::custom_keyword!(asset);
+  syn::custom_keyword!(file);
+}
+
+/// Arguments of the `#[assets]` attribute, e.g.: `#[assets(file = "assets.toml")]`
+#[derive(Debug, Default)]
+pub struct AssetsAttr {
+  pub file: Option<syn::LitStr>,
+}
+
+impl syn::parse::Parse for AssetsAttr {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    if input.is_empty() {
+      return Ok(AssetsAttr::default());
+    }
+    input.parse::<keyword::file>()?;
+    input.parse::<syn::Token![=]>()?;
+    let file = input.parse::<syn::LitStr>()?;
+
+    Ok(AssetsAttr { file: Some(file) })
+  }
 }
 
 mod keyword_fn {
@@ -33,6 +52,35 @@ mod keyword_fn {
   syn::custom_keyword!(base_chain);
   syn::custom_keyword!(min_stake);
   syn::custom_keyword!(max_stake);
+  syn::custom_keyword!(alias);
+  syn::custom_keyword!(network);
+  syn::custom_keyword!(native);
+  syn::custom_keyword!(status);
+  syn::custom_keyword!(deprecated);
+  syn::custom_keyword!(denomination);
+}
+
+mod keyword_deprecated {
+  syn::custom_keyword!(since);
+}
+
+mod keyword_denomination {
+  syn::custom_keyword!(exponent);
+}
+
+mod keyword_network {
+  syn::custom_keyword!(address);
+  syn::custom_keyword!(router);
+  syn::custom_keyword!(multisig);
+  syn::custom_keyword!(chain_id);
+  syn::custom_keyword!(enabled);
+  syn::custom_keyword!(enabled_from);
+  syn::custom_keyword!(disabled_at);
+}
+
+mod keyword_schedule {
+  syn::custom_keyword!(block);
+  syn::custom_keyword!(timestamp);
 }
 
 #[derive(Debug)]
@@ -46,8 +94,13 @@ pub enum FnAttr {
   Prefix(String, proc_macro2::Span),
   Pot(bool, proc_macro2::Span),
   BaseChain(syn::Ident, proc_macro2::Span),
-  MinStake(u128, proc_macro2::Span),
-  MaxStake(u128, proc_macro2::Span),
+  MinStake(Amount, proc_macro2::Span),
+  MaxStake(Amount, proc_macro2::Span),
+  Alias(syn::LitStr, proc_macro2::Span),
+  Network(NetworkAttr, proc_macro2::Span),
+  Native(bool, proc_macro2::Span),
+  Lifecycle(Lifecycle, proc_macro2::Span),
+  Denomination(DenominationAttr, proc_macro2::Span),
 }
 
 impl FnAttr {
@@ -63,7 +116,12 @@ impl FnAttr {
       | Self::Pot(_, span)
       | Self::BaseChain(_, span)
       | Self::MinStake(_, span)
-      | Self::MaxStake(_, span) => *span,
+      | Self::MaxStake(_, span)
+      | Self::Alias(_, span)
+      | Self::Network(_, span)
+      | Self::Native(_, span)
+      | Self::Lifecycle(_, span)
+      | Self::Denomination(_, span) => *span,
     }
   }
 }
@@ -82,10 +140,11 @@ impl syn::parse::Parse for FnAttr {
       content.parse::<keyword_fn::symbol>()?;
       content.parse::<syn::Token![=]>()?;
       let renamed_prefix = content.parse::<syn::LitStr>()?;
-      let new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
+      let mut new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
         let msg = format!("`{}` is not a valid identifier", renamed_prefix.value());
         syn::Error::new(renamed_prefix.span(), msg)
       })?;
+      new_ident.set_span(renamed_prefix.span());
 
       Ok(Self::Symbol(new_ident, attr_span))
     } else if lookahead.peek(keyword_fn::name) {
@@ -121,10 +180,11 @@ impl syn::parse::Parse for FnAttr {
       content.parse::<syn::Token![=]>()?;
 
       let renamed_prefix = content.parse::<syn::LitStr>()?;
-      let new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
+      let mut new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
         let msg = format!("`{}` is not a valid identifier", renamed_prefix.value());
         syn::Error::new(renamed_prefix.span(), msg)
       })?;
+      new_ident.set_span(renamed_prefix.span());
 
       Ok(Self::Algo(new_ident, attr_span))
     } else if lookahead.peek(keyword_fn::unit) {
@@ -132,10 +192,11 @@ impl syn::parse::Parse for FnAttr {
       content.parse::<syn::Token![=]>()?;
 
       let renamed_prefix = content.parse::<syn::LitStr>()?;
-      let new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
+      let mut new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
         let msg = format!("`{}` is not a valid identifier", renamed_prefix.value());
         syn::Error::new(renamed_prefix.span(), msg)
       })?;
+      new_ident.set_span(renamed_prefix.span());
 
       Ok(Self::Unit(new_ident, attr_span))
     } else if lookahead.peek(keyword_fn::prefix) {
@@ -153,52 +214,365 @@ impl syn::parse::Parse for FnAttr {
       content.parse::<syn::Token![=]>()?;
 
       let renamed_prefix = content.parse::<syn::LitStr>()?;
-      let new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
+      let mut new_ident = syn::parse_str::<syn::Ident>(&renamed_prefix.value()).map_err(|_| {
         let msg = format!("`{}` is not a valid identifier", renamed_prefix.value());
         syn::Error::new(renamed_prefix.span(), msg)
       })?;
+      new_ident.set_span(renamed_prefix.span());
 
       Ok(Self::BaseChain(new_ident, attr_span))
     } else if lookahead.peek(keyword_fn::min_stake) {
       content.parse::<keyword_fn::min_stake>()?;
       content.parse::<syn::Token![=]>()?;
-      let new_ident = content.parse::<syn::LitInt>()?;
-
-      let decimals = new_ident.base10_parse::<u128>().map_err(|_| {
-        let msg = format!("`{}` is not a valid identifier", new_ident.base10_digits());
-        syn::Error::new(new_ident.span(), msg)
-      })?;
 
-      Ok(Self::MinStake(decimals, attr_span))
+      Ok(Self::MinStake(content.parse()?, attr_span))
     } else if lookahead.peek(keyword_fn::max_stake) {
       content.parse::<keyword_fn::max_stake>()?;
       content.parse::<syn::Token![=]>()?;
-      let new_ident = content.parse::<syn::LitInt>()?;
 
-      let decimals = new_ident.base10_parse::<u128>().map_err(|_| {
-        let msg = format!("`{}` is not a valid identifier", new_ident.base10_digits());
-        syn::Error::new(new_ident.span(), msg)
+      Ok(Self::MaxStake(content.parse()?, attr_span))
+    } else if lookahead.peek(keyword_fn::alias) {
+      content.parse::<keyword_fn::alias>()?;
+      content.parse::<syn::Token![=]>()?;
+      let alias = content.parse::<syn::LitStr>()?;
+
+      Ok(Self::Alias(alias, attr_span))
+    } else if lookahead.peek(keyword_fn::network) {
+      content.parse::<keyword_fn::network>()?;
+      let network;
+      syn::parenthesized!(network in content);
+
+      Ok(Self::Network(network.parse()?, attr_span))
+    } else if lookahead.peek(keyword_fn::native) {
+      content.parse::<keyword_fn::native>()?;
+      Ok(Self::Native(true, attr_span))
+    } else if lookahead.peek(keyword_fn::status) {
+      content.parse::<keyword_fn::status>()?;
+      content.parse::<syn::Token![=]>()?;
+      let status = content.parse::<syn::LitStr>()?;
+      let lifecycle = Lifecycle::from_status(&status.value())
+        .map_err(|msg| syn::Error::new(status.span(), msg))?;
+
+      Ok(Self::Lifecycle(lifecycle, attr_span))
+    } else if lookahead.peek(keyword_fn::deprecated) {
+      content.parse::<keyword_fn::deprecated>()?;
+      let mut since = None;
+      if !content.is_empty() {
+        let deprecated;
+        syn::parenthesized!(deprecated in content);
+        deprecated.parse::<keyword_deprecated::since>()?;
+        deprecated.parse::<syn::Token![=]>()?;
+        since = Some(deprecated.parse::<syn::LitStr>()?.value());
+      }
+
+      Ok(Self::Lifecycle(Lifecycle::Deprecated(since), attr_span))
+    } else if lookahead.peek(keyword_fn::denomination) {
+      content.parse::<keyword_fn::denomination>()?;
+      let denomination;
+      syn::parenthesized!(denomination in content);
+
+      Ok(Self::Denomination(denomination.parse()?, attr_span))
+    } else {
+      Err(lookahead.error())
+    }
+  }
+}
+
+/// Lifecycle of an asset, declared with `#[asset::status = "…"]` or `#[asset::deprecated(since = "…")]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lifecycle {
+  Listed,
+  Deprecated(Option<String>),
+  Delisted,
+}
+
+impl Lifecycle {
+  pub fn from_status(status: &str) -> Result<Self, String> {
+    match status {
+      "listed" => Ok(Lifecycle::Listed),
+      "deprecated" => Ok(Lifecycle::Deprecated(None)),
+      "delisted" => Ok(Lifecycle::Delisted),
+      _ => Err(format!(
+        "`{}` is not a valid status, expected one of: listed, deprecated, delisted",
+        status
+      )),
+    }
+  }
+}
+
+/// A stake limit, in base units, e.g. `#[asset::min_stake = 100]`,
+/// or in units of the asset scaled by its decimals, e.g. `#[asset::min_stake = "0.5"]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Amount {
+  Base(u128),
+  Units(String),
+}
+
+impl Amount {
+  /// Convert the amount to base units of an asset with `decimals`.
+  pub fn to_base(&self, decimals: u8) -> Result<u128, String> {
+    let units = match self {
+      Amount::Base(amount) => return Ok(*amount),
+      Amount::Units(units) => units,
+    };
+    let digits = units.replace('_', "");
+    let (integer, fraction) = match digits.split_once('.') {
+      Some((integer, fraction)) if !fraction.is_empty() => (integer, fraction),
+      Some(_) => ("", ""),
+      None => (digits.as_str(), ""),
+    };
+    if integer.is_empty()
+      || !integer.bytes().all(|digit| digit.is_ascii_digit())
+      || !fraction.bytes().all(|digit| digit.is_ascii_digit())
+    {
+      return Err(format!(
+        "`{}` is not a valid amount, expected a decimal number such as \"10\" or \"0.5\"",
+        units
+      ));
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals.into() {
+      return Err(format!(
+        "`{}` has more than {} decimals and would lose precision",
+        units, decimals
+      ));
+    }
+
+    let overflow = || format!("`{}` with {} decimals overflows `Balance`", units, decimals);
+    let scale = 10_u128.checked_pow(decimals.into()).ok_or_else(overflow)?;
+    let integer = integer.parse::<u128>().map_err(|_| overflow())?;
+    // at most 38 digits, as `scale` would have overflowed otherwise
+    let fraction = match fraction {
+      "" => 0,
+      fraction => {
+        fraction.parse::<u128>().map_err(|_| overflow())?
+          * 10_u128.pow((usize::from(decimals) - fraction.len()) as u32)
+      }
+    };
+
+    integer
+      .checked_mul(scale)
+      .and_then(|amount| amount.checked_add(fraction))
+      .ok_or_else(overflow)
+  }
+}
+
+impl syn::parse::Parse for Amount {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(syn::LitStr) {
+      Ok(Amount::Units(input.parse::<syn::LitStr>()?.value()))
+    } else if lookahead.peek(syn::LitInt) {
+      let amount = input.parse::<syn::LitInt>()?;
+      let amount = amount.base10_parse::<u128>().map_err(|_| {
+        let msg = format!("`{}` is not a valid amount", amount.base10_digits());
+        syn::Error::new(amount.span(), msg)
       })?;
+      Ok(Amount::Base(amount))
+    } else {
+      Err(lookahead.error())
+    }
+  }
+}
+
+/// Bound of the enablement schedule of an asset on a network, e.g.: `block(15_000_000)` or
+/// `timestamp(1_672_531_200_000)` in milliseconds since the UNIX epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum SchedulePoint {
+  Block(u32),
+  Timestamp(u64),
+}
 
-      Ok(Self::MaxStake(decimals, attr_span))
+impl syn::parse::Parse for SchedulePoint {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(keyword_schedule::block) {
+      input.parse::<keyword_schedule::block>()?;
+      let content;
+      syn::parenthesized!(content in input);
+      Ok(SchedulePoint::Block(
+        content.parse::<syn::LitInt>()?.base10_parse()?,
+      ))
+    } else if lookahead.peek(keyword_schedule::timestamp) {
+      input.parse::<keyword_schedule::timestamp>()?;
+      let content;
+      syn::parenthesized!(content in input);
+      Ok(SchedulePoint::Timestamp(
+        content.parse::<syn::LitInt>()?.base10_parse()?,
+      ))
     } else {
       Err(lookahead.error())
     }
   }
 }
 
+// a block and a timestamp aren't comparable
+impl PartialOrd for SchedulePoint {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    match (self, other) {
+      (SchedulePoint::Block(a), SchedulePoint::Block(b)) => a.partial_cmp(b),
+      (SchedulePoint::Timestamp(a), SchedulePoint::Timestamp(b)) => a.partial_cmp(b),
+      _ => None,
+    }
+  }
+}
+
+impl std::fmt::Display for SchedulePoint {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      SchedulePoint::Block(block) => write!(f, "block({})", block),
+      SchedulePoint::Timestamp(timestamp) => write!(f, "timestamp({})", timestamp),
+    }
+  }
+}
+
+/// Deployment data of an asset on a network, e.g.:
+/// `#[asset::network(mainnet, address = "0x…", chain_id = 1, enabled = true)]`, the asset can be
+/// listed or delisted later with `enabled_from = block(…)` and `disabled_at = timestamp(…)`.
+#[derive(Debug)]
+pub struct NetworkAttr {
+  pub name: syn::Ident,
+  pub address: Option<String>,
+  pub router: Option<String>,
+  pub multisig: Option<String>,
+  pub chain_id: Option<u32>,
+  pub enabled: bool,
+  pub enabled_from: Option<SchedulePoint>,
+  pub disabled_at: Option<SchedulePoint>,
+}
+
+impl syn::parse::Parse for NetworkAttr {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let name = input.parse::<syn::Ident>()?;
+    let mut address = None;
+    let mut router = None;
+    let mut multisig = None;
+    let mut chain_id = None;
+    let mut enabled = None;
+    let mut enabled_from = None;
+    let mut disabled_at = None;
+
+    while !input.is_empty() {
+      input.parse::<syn::Token![,]>()?;
+      if input.is_empty() {
+        break;
+      }
+
+      let key_span = input.span();
+      let lookahead = input.lookahead1();
+      let is_duplicate = if lookahead.peek(keyword_network::address) {
+        input.parse::<keyword_network::address>()?;
+        input.parse::<syn::Token![=]>()?;
+        address
+          .replace(input.parse::<syn::LitStr>()?.value())
+          .is_some()
+      } else if lookahead.peek(keyword_network::router) {
+        input.parse::<keyword_network::router>()?;
+        input.parse::<syn::Token![=]>()?;
+        router
+          .replace(input.parse::<syn::LitStr>()?.value())
+          .is_some()
+      } else if lookahead.peek(keyword_network::multisig) {
+        input.parse::<keyword_network::multisig>()?;
+        input.parse::<syn::Token![=]>()?;
+        multisig
+          .replace(input.parse::<syn::LitStr>()?.value())
+          .is_some()
+      } else if lookahead.peek(keyword_network::chain_id) {
+        input.parse::<keyword_network::chain_id>()?;
+        input.parse::<syn::Token![=]>()?;
+        let new_ident = input.parse::<syn::LitInt>()?;
+        let found_chain_id = new_ident.base10_parse::<u32>().map_err(|_| {
+          let msg = format!("`{}` is not a valid chain id", new_ident.base10_digits());
+          syn::Error::new(new_ident.span(), msg)
+        })?;
+        chain_id.replace(found_chain_id).is_some()
+      } else if lookahead.peek(keyword_network::enabled_from) {
+        input.parse::<keyword_network::enabled_from>()?;
+        input.parse::<syn::Token![=]>()?;
+        enabled_from.replace(input.parse()?).is_some()
+      } else if lookahead.peek(keyword_network::disabled_at) {
+        input.parse::<keyword_network::disabled_at>()?;
+        input.parse::<syn::Token![=]>()?;
+        disabled_at.replace(input.parse()?).is_some()
+      } else if lookahead.peek(keyword_network::enabled) {
+        input.parse::<keyword_network::enabled>()?;
+        input.parse::<syn::Token![=]>()?;
+        enabled
+          .replace(input.parse::<syn::LitBool>()?.value)
+          .is_some()
+      } else {
+        return Err(lookahead.error());
+      };
+
+      if is_duplicate {
+        return Err(syn::Error::new(
+          key_span,
+          "Invalid attribute: Duplicate network key",
+        ));
+      }
+    }
+
+    Ok(NetworkAttr {
+      name,
+      address,
+      router,
+      multisig,
+      chain_id,
+      // declaring a network without `enabled` means the asset is live there
+      enabled: enabled.unwrap_or(true),
+      enabled_from,
+      disabled_at,
+    })
+  }
+}
+
+/// Additional denomination of an asset, relative to its base unit, e.g.:
+/// `#[asset::denomination(gwei, exponent = 9)]`
+#[derive(Debug)]
+pub struct DenominationAttr {
+  pub name: syn::Ident,
+  pub exponent: u8,
+}
+
+impl syn::parse::Parse for DenominationAttr {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let name = input.parse::<syn::Ident>()?;
+    input.parse::<syn::Token![,]>()?;
+    input.parse::<keyword_denomination::exponent>()?;
+    input.parse::<syn::Token![=]>()?;
+    let exponent = input.parse::<syn::LitInt>()?;
+    let exponent = exponent.base10_parse::<u8>().map_err(|_| {
+      let msg = format!("`{}` is not a valid exponent", exponent.base10_digits());
+      syn::Error::new(exponent.span(), msg)
+    })?;
+    if input.peek(syn::Token![,]) {
+      input.parse::<syn::Token![,]>()?;
+    }
+
+    Ok(DenominationAttr { name, exponent })
+  }
+}
+
 struct FnAttrInfo {
-  id: u32,
+  id: (u32, proc_macro2::Span),
   symbol: syn::Ident,
-  name: String,
-  decimals: u8,
+  name: (String, proc_macro2::Span),
+  decimals: (u8, proc_macro2::Span),
   algo: syn::Ident,
   unit: Option<syn::Ident>,
   prefix: Option<String>,
   pot: bool,
   base_chain: Option<syn::Ident>,
-  min_stake: u128,
-  max_stake: u128,
+  min_stake: (Amount, proc_macro2::Span),
+  max_stake: (Amount, proc_macro2::Span),
+  aliases: Vec<syn::LitStr>,
+  networks: Vec<NetworkAttr>,
+  native: Option<proc_macro2::Span>,
+  lifecycle: Lifecycle,
+  denominations: Vec<DenominationAttr>,
 }
 
 impl FnAttrInfo {
@@ -214,19 +588,35 @@ impl FnAttrInfo {
     let mut base_chain = None;
     let mut min_stake = None;
     let mut max_stake = None;
+    let mut aliases = Vec::new();
+    let mut networks = Vec::new();
+    let mut native = None;
+    let mut lifecycle = None;
+    let mut denominations = Vec::new();
 
     for attr in attrs {
       match attr {
-        FnAttr::Id(ident, ..) if id.is_none() => id = Some(ident),
+        FnAttr::Id(ident, span) if id.is_none() => id = Some((ident, span)),
         FnAttr::Symbol(ident, ..) if symbol.is_none() => symbol = Some(ident),
-        FnAttr::Name(ident, ..) if name.is_none() => name = Some(ident),
-        FnAttr::Decimals(found_rpc, ..) if decimals.is_none() => decimals = Some(found_rpc),
+        FnAttr::Name(ident, span) if name.is_none() => name = Some((ident, span)),
+        FnAttr::Decimals(found_rpc, span) if decimals.is_none() => {
+          decimals = Some((found_rpc, span))
+        }
         FnAttr::Algo(found_const, ..) if algo.is_none() => algo = Some(found_const),
         FnAttr::Unit(found_const, ..) if unit.is_none() => unit = Some(found_const),
         FnAttr::Prefix(found_const, ..) if prefix.is_none() => prefix = Some(found_const),
-        FnAttr::MinStake(found_const, ..) if min_stake.is_none() => min_stake = Some(found_const),
-        FnAttr::MaxStake(found_const, ..) if max_stake.is_none() => max_stake = Some(found_const),
+        FnAttr::MinStake(found_const, span) if min_stake.is_none() => {
+          min_stake = Some((found_const, span))
+        }
+        FnAttr::MaxStake(found_const, span) if max_stake.is_none() => {
+          max_stake = Some((found_const, span))
+        }
         FnAttr::Pot(found_const, ..) => pot = found_const,
+        FnAttr::Alias(found_const, ..) => aliases.push(found_const),
+        FnAttr::Network(found_const, ..) => networks.push(found_const),
+        FnAttr::Denomination(found_const, ..) => denominations.push(found_const),
+        FnAttr::Native(true, span) if native.is_none() => native = Some(span),
+        FnAttr::Lifecycle(found_const, ..) if lifecycle.is_none() => lifecycle = Some(found_const),
         FnAttr::BaseChain(found_const, ..) if base_chain.is_none() => {
           base_chain = Some(found_const)
         }
@@ -254,6 +644,11 @@ impl FnAttrInfo {
       prefix,
       pot,
       base_chain,
+      aliases,
+      networks,
+      native,
+      lifecycle: lifecycle.unwrap_or(Lifecycle::Listed),
+      denominations,
     })
   }
 }
@@ -261,70 +656,542 @@ impl FnAttrInfo {
 #[derive(Debug)]
 pub struct Asset {
   pub id: u32,
+  pub id_span: proc_macro2::Span,
   pub inner: syn::Variant,
   pub symbol: syn::Ident,
   pub name: String,
+  pub name_span: proc_macro2::Span,
   pub decimals: u8,
+  pub decimals_span: proc_macro2::Span,
   pub algo: syn::Ident,
   pub unit: Option<syn::Ident>,
   pub prefix: Option<String>,
   pub pot: bool,
   pub base_chain: Option<syn::Ident>,
   pub min_stake: u128,
+  pub min_stake_span: proc_macro2::Span,
   pub max_stake: u128,
+  pub aliases: Vec<syn::LitStr>,
+  pub networks: Vec<NetworkAttr>,
+  pub native: Option<proc_macro2::Span>,
+  pub lifecycle: Lifecycle,
+  pub denominations: Vec<DenominationAttr>,
+  pub origin: Option<manifest::Origin>,
 }
 
 impl Asset {
   pub fn try_from(item: &mut syn::Variant) -> syn::Result<Self> {
-    let attrs: Vec<FnAttr> = utils::take_item_assets_attrs(&mut item.attrs.clone()).unwrap();
+    let attrs: Vec<FnAttr> = utils::take_item_assets_attrs(&mut item.attrs.clone())?;
 
     let FnAttrInfo {
-      id,
+      id: (id, id_span),
       symbol,
-      name,
-      decimals,
+      name: (name, name_span),
+      decimals: (decimals, decimals_span),
       algo,
       unit,
       prefix,
       pot,
       base_chain,
-      min_stake,
-      max_stake,
-    } = FnAttrInfo::from_attrs(attrs, item.span()).unwrap();
+      min_stake: (min_stake, min_stake_span),
+      max_stake: (max_stake, max_stake_span),
+      aliases,
+      networks,
+      native,
+      lifecycle,
+      denominations,
+    } = FnAttrInfo::from_attrs(attrs, item.ident.span())?;
+
+    let min_stake = min_stake
+      .to_base(decimals)
+      .map_err(|msg| syn::Error::new(min_stake_span, msg))?;
+    let max_stake = max_stake
+      .to_base(decimals)
+      .map_err(|msg| syn::Error::new(max_stake_span, msg))?;
 
     Ok(Asset {
       id,
+      id_span,
       inner: item.clone(),
       symbol,
       name,
+      name_span,
       decimals,
+      decimals_span,
       algo,
       unit,
       prefix,
       pot,
       base_chain,
       min_stake,
+      min_stake_span,
       max_stake,
+      aliases,
+      networks,
+      native,
+      lifecycle,
+      denominations,
+      origin: None,
     })
   }
+
+  /// Create an error, located in the manifest file when the asset has been loaded from one.
+  pub fn error(&self, key: &str, span: proc_macro2::Span, msg: String) -> syn::Error {
+    match &self.origin {
+      Some(origin) => syn::Error::new(span, format!("{}: {}", origin.position(key), msg)),
+      None => syn::Error::new(span, msg),
+    }
+  }
+
+  /// Strings identifying this asset when parsed: its symbol, name and aliases.
+  pub fn lookup_keys(&self) -> Vec<(String, proc_macro2::Span)> {
+    let mut keys = vec![
+      (self.symbol.to_string(), self.symbol.span()),
+      (self.name.clone(), self.name_span),
+    ];
+    keys.extend(
+      self
+        .aliases
+        .iter()
+        .map(|alias| (alias.value(), alias.span())),
+    );
+    keys
+  }
+
+  /// Every denomination of the asset, with its exponent relative to the base unit: the unit, the
+  /// declared denominations and the symbol, sorted by exponent.
+  pub fn denomination_table(&self) -> Vec<(String, u8)> {
+    let mut table = Vec::new();
+    if let Some(unit) = &self.unit {
+      table.push((unit.to_string(), 0));
+    }
+    table.extend(
+      self
+        .denominations
+        .iter()
+        .map(|denomination| (denomination.name.to_string(), denomination.exponent)),
+    );
+    table.push((self.symbol.to_string(), self.decimals));
+    table.sort_by_key(|(_, exponent)| *exponent);
+    table
+  }
+
+  /// Validate the rules which only involve this asset.
+  fn validate(&self) -> syn::Result<()> {
+    let mut errors = Errors::default();
+
+    if !ALGOS.contains(&self.algo.to_string().as_str()) {
+      errors.push(self.error(
+        "algo",
+        self.algo.span(),
+        format!(
+          "`{}` is not a valid `Algo`, expected one of: {}",
+          self.algo,
+          ALGOS.join(", ")
+        ),
+      ));
+    }
+
+    if 10_u128.checked_pow(self.decimals.into()).is_none() {
+      errors.push(self.error(
+        "decimals",
+        self.decimals_span,
+        format!(
+          "`{}` decimals overflow `Balance`: `10^{}` is greater than `u128::MAX`",
+          self.decimals, self.decimals
+        ),
+      ));
+    }
+
+    if self.min_stake > self.max_stake {
+      errors.push(self.error(
+        "min_stake",
+        self.min_stake_span,
+        format!(
+          "`min_stake` ({}) is greater than `max_stake` ({})",
+          self.min_stake, self.max_stake
+        ),
+      ));
+    }
+
+    for (index, network) in self.networks.iter().enumerate() {
+      let key = format!("network.{}", network.name);
+      if !NETWORKS.contains(&network.name.to_string().as_str()) {
+        errors.push(self.error(
+          &key,
+          network.name.span(),
+          format!(
+            "`{}` is not a valid `Network`, expected one of: {}",
+            network.name,
+            NETWORKS.join(", ")
+          ),
+        ));
+      }
+
+      if !network.enabled && (network.enabled_from.is_some() || network.disabled_at.is_some()) {
+        errors.push(self.error(
+          &key,
+          network.name.span(),
+          format!(
+            "`{}` is disabled, `enabled_from` and `disabled_at` require `enabled = true`",
+            network.name
+          ),
+        ));
+      }
+      if let (Some(from), Some(to)) = (network.enabled_from, network.disabled_at) {
+        match from.partial_cmp(&to) {
+          None => errors.push(self.error(
+            &key,
+            network.name.span(),
+            format!(
+              "`enabled_from = {}` and `disabled_at = {}` must both be blocks or timestamps",
+              from, to
+            ),
+          )),
+          Some(std::cmp::Ordering::Less) => (),
+          Some(_) => errors.push(self.error(
+            &key,
+            network.name.span(),
+            format!(
+              "`enabled_from = {}` must be before `disabled_at = {}`",
+              from, to
+            ),
+          )),
+        }
+      }
+
+      let addresses = [
+        ("address", &network.address),
+        ("router", &network.router),
+        ("multisig", &network.multisig),
+      ];
+      for (field, address) in addresses {
+        if let Some(Err(msg)) = address.as_deref().map(evm_address) {
+          errors.push(self.error(
+            &key,
+            network.name.span(),
+            format!(
+              "`{}` `{}` {}",
+              field,
+              address.as_deref().unwrap_or_default(),
+              msg
+            ),
+          ));
+        }
+      }
+
+      if self.networks[..index]
+        .iter()
+        .any(|other| other.name == network.name)
+      {
+        errors.push(self.error(
+          &key,
+          network.name.span(),
+          format!("Duplicate `#[asset::network({}, ..)]`", network.name),
+        ));
+      }
+    }
+
+    for (index, denomination) in self.denominations.iter().enumerate() {
+      let key = format!("denomination.{}", denomination.name);
+      if denomination.exponent > self.decimals {
+        errors.push(self.error(
+          &key,
+          denomination.name.span(),
+          format!(
+            "`{}` exponent ({}) is greater than the decimals of the asset ({})",
+            denomination.name, denomination.exponent, self.decimals
+          ),
+        ));
+      }
+
+      let is_duplicate = self.denominations[..index]
+        .iter()
+        .any(|other| other.name == denomination.name);
+      if is_duplicate
+        || Some(&denomination.name) == self.unit.as_ref()
+        || denomination.name == self.symbol
+      {
+        errors.push(self.error(
+          &key,
+          denomination.name.span(),
+          format!("Duplicate denomination `{}`", denomination.name),
+        ));
+      }
+    }
+
+    errors.finish()
+  }
+}
+
+/// Variants of the `Network` enum, as displayed in lowercase.
+///
+/// Kept in sync with `Network::iter()` by a test in `tidefi-primitives`.
+pub(crate) const NETWORKS: &[&str] = &["local", "devnet", "staging", "testnet", "mainnet"];
+
+/// Variants of the `Algo` enum declared next to the assets registry.
+///
+/// Kept in sync with `Algo` by a test in `tidefi-primitives`.
+pub(crate) const ALGOS: &[&str] = &["SR25519", "SECP256K1", "WEB3"];
+
+/// Bytes of a `0x` prefixed EVM address, its EIP-55 checksum is verified when it mixes cases, as
+/// `EvmAddress::from_str` does.
+pub fn evm_address(address: &str) -> Result<[u8; 20], &'static str> {
+  use sha3::{Digest, Keccak256};
+
+  let hex = address
+    .strip_prefix("0x")
+    .filter(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+    .ok_or("is not an EVM address, expected `0x` followed by 40 hex digits")?;
+
+  let mut bytes = [0; 20];
+  for (index, byte) in bytes.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).expect("hex digits");
+  }
+
+  let has_lowercase = hex.chars().any(|c| c.is_ascii_lowercase());
+  let has_uppercase = hex.chars().any(|c| c.is_ascii_uppercase());
+  if has_lowercase && has_uppercase {
+    let hash = Keccak256::digest(hex.to_ascii_lowercase().as_bytes());
+    let is_checksummed = hex.chars().enumerate().all(|(index, c)| {
+      let nibble = (hash[index / 2] >> (4 * (1 - index % 2))) & 0xf;
+      c.is_ascii_digit() || c.is_ascii_uppercase() == (nibble >= 8)
+    });
+    if !is_checksummed {
+      return Err("doesn't match its EIP-55 checksum");
+    }
+  }
+  Ok(bytes)
+}
+
+/// Accumulate every error found so they can be reported at once.
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+  fn push(&mut self, error: syn::Error) {
+    match &mut self.0 {
+      Some(errors) => errors.combine(error),
+      None => self.0 = Some(error),
+    }
+  }
+
+  fn finish(self) -> syn::Result<()> {
+    match self.0 {
+      Some(errors) => Err(errors),
+      None => Ok(()),
+    }
+  }
 }
 
 #[derive(Debug)]
 pub struct Def {
   pub item: syn::ItemEnum,
   pub assets: Vec<Asset>,
+  /// Absolute path of the manifest the assets have been loaded from.
+  pub manifest: Option<String>,
 }
 
 impl Def {
-  pub fn try_from(mut item: syn::ItemEnum) -> syn::Result<Self> {
-    let assets = item
-      .variants
-      .iter_mut()
-      .map(|asset_item| Asset::try_from(asset_item).unwrap())
-      .collect();
+  pub fn try_from(mut item: syn::ItemEnum, attr: AssetsAttr) -> syn::Result<Self> {
+    let mut errors = Errors::default();
+    let mut assets = Vec::new();
+    let mut manifest = None;
 
-    let def = Def { assets, item };
+    if let Some(file) = attr.file {
+      if let Some(variant) = item.variants.first() {
+        let msg = format!(
+          "Assets are declared in `{}`, the enum must not have variants",
+          file.value()
+        );
+        return Err(syn::Error::new(variant.ident.span(), msg));
+      }
+
+      let (path, loaded) = manifest::load(&file)?;
+      manifest = Some(path);
+      item
+        .variants
+        .extend(loaded.iter().map(|asset| asset.inner.clone()));
+      assets = loaded;
+    }
+
+    for asset_item in item.variants.iter_mut().skip(assets.len()) {
+      match Asset::try_from(asset_item) {
+        Ok(asset) => assets.push(asset),
+        Err(e) => errors.push(e),
+      }
+    }
+    errors.finish()?;
+
+    let def = Def {
+      assets,
+      item,
+      manifest,
+    };
+    def.validate()?;
 
     Ok(def)
   }
+
+  /// The asset marked with `#[asset::native]`, mapped to `CurrencyId::Tdfy`.
+  pub fn native(&self) -> &Asset {
+    self
+      .assets
+      .iter()
+      .find(|asset| asset.native.is_some())
+      .expect("validated in `Def::try_from`")
+  }
+
+  /// Validate every asset, then the rules spanning the whole registry.
+  fn validate(&self) -> syn::Result<()> {
+    let mut errors = Errors::default();
+
+    let mut natives = self.assets.iter().filter(|asset| asset.native.is_some());
+    if natives.next().is_none() {
+      errors.push(syn::Error::new(
+        self.item.ident.span(),
+        "Missing `#[asset::native]`: one asset must be mapped to `CurrencyId::Tdfy`",
+      ));
+    }
+    for asset in natives {
+      errors.push(asset.error(
+        "native",
+        asset.native.expect("filtered on native assets"),
+        "Duplicate `#[asset::native]`: only one asset can be mapped to `CurrencyId::Tdfy`".into(),
+      ));
+    }
+
+    for (index, asset) in self.assets.iter().enumerate() {
+      if let Err(e) = asset.validate() {
+        errors.push(e);
+      }
+
+      let previous = &self.assets[..index];
+      if let Some(other) = previous.iter().find(|other| other.id == asset.id) {
+        errors.push(asset.error(
+          "id",
+          asset.id_span,
+          format!(
+            "Duplicate `#[asset::id]`: `{}` is already used by `{}`",
+            asset.id, other.inner.ident
+          ),
+        ));
+      }
+
+      // symbols are looked up case-insensitively
+      let symbol = asset.symbol.to_string();
+      if let Some(other) = previous
+        .iter()
+        .find(|other| other.symbol.to_string().eq_ignore_ascii_case(&symbol))
+      {
+        errors.push(asset.error(
+          "symbol",
+          asset.symbol.span(),
+          format!(
+            "Duplicate `#[asset::symbol]`: `{}` is already used by `{}`",
+            asset.symbol, other.inner.ident
+          ),
+        ));
+      }
+
+      // duplicated symbols are reported above, so only names and aliases are checked here,
+      // against every key of the previous assets and the symbols of the next ones
+      for (key_index, (key, span)) in asset.lookup_keys().into_iter().enumerate().skip(1) {
+        let ambiguous = self.assets.iter().enumerate().find(|(other_index, other)| {
+          let other_keys = other.lookup_keys();
+          let other_keys = match *other_index {
+            i if i < index => &other_keys[..],
+            i if i > index => &other_keys[..1],
+            _ => &[],
+          };
+          other_keys
+            .iter()
+            .any(|(other_key, _)| other_key.eq_ignore_ascii_case(&key))
+        });
+        if let Some((_, other)) = ambiguous {
+          errors.push(asset.error(
+            if key_index == 1 { "name" } else { "aliases" },
+            span,
+            format!(
+              "`{}` is ambiguous: it already identifies `{}`",
+              key, other.inner.ident
+            ),
+          ));
+        }
+      }
+
+      if let Some(base_chain) = &asset.base_chain {
+        if !self
+          .assets
+          .iter()
+          .any(|other| &other.inner.ident == base_chain)
+        {
+          errors.push(asset.error(
+            "base_chain",
+            base_chain.span(),
+            format!("`{}` is not a variant of `{}`", base_chain, self.item.ident),
+          ));
+        }
+      }
+    }
+
+    errors.finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_amount_to_base() {
+    let units = |units: &str, decimals| Amount::Units(units.into()).to_base(decimals);
+
+    assert_eq!(Amount::Base(100).to_base(8), Ok(100));
+    assert_eq!(units("10", 12), Ok(10_000_000_000_000));
+    assert_eq!(units("500000.5", 8), Ok(50_000_050_000_000));
+    assert_eq!(units("0.000_001", 8), Ok(100));
+    assert_eq!(units("1.500", 1), Ok(15));
+    assert_eq!(units("7", 0), Ok(7));
+
+    assert!(units("0.001", 2)
+      .unwrap_err()
+      .contains("would lose precision"));
+    assert!(units("340282366920938463463374607431768211456", 0)
+      .unwrap_err()
+      .contains("overflows"));
+    assert!(units("1000", 36).unwrap_err().contains("overflows"));
+    assert!(units("1", 39).unwrap_err().contains("overflows"));
+    for invalid in ["", ".5", "1.", "-1", "1e3", "1.2.3", " 1"] {
+      assert!(units(invalid, 6)
+        .unwrap_err()
+        .contains("is not a valid amount"));
+    }
+  }
+
+  #[test]
+  fn test_evm_address() {
+    let bytes = evm_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap();
+    assert_eq!(bytes[..4], [0x5a, 0xae, 0xb6, 0x05]);
+    assert_eq!(bytes[19], 0xed);
+    assert_eq!(
+      evm_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+      Ok(bytes)
+    );
+    assert_eq!(
+      evm_address("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"),
+      Ok(bytes)
+    );
+    assert_eq!(
+      evm_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+      Err("doesn't match its EIP-55 checksum")
+    );
+    for invalid in [
+      "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+      "0x5aaeb6",
+      "0xzaaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+    ] {
+      assert!(evm_address(invalid).is_err());
+    }
+  }
 }
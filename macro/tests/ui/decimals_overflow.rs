@@ -0,0 +1,32 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+use tidefi_primitives_macro::assets;
+
+#[assets]
+pub enum Asset {
+  #[asset::id = 1]
+  #[asset::symbol = "TDFY"]
+  #[asset::native]
+  #[asset::name = "Tidefi Token"]
+  #[asset::decimals = 39]
+  #[asset::algo = "SR25519"]
+  #[asset::min_stake = 100]
+  #[asset::max_stake = 500]
+  Tdfy,
+}
+
+fn main() {}
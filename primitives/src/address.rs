@@ -0,0 +1,397 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validation of the external addresses funds are withdrawn to, e.g.: a Bitcoin address for BTC
+//! or an EVM address for ETH and the ERC-20 tokens.
+
+use crate::{
+  assets::{Algo, Asset},
+  evm::EvmAddress,
+  networks::{BitcoinNetwork, Network},
+  AccountId,
+};
+use sp_core::hashing::sha2_256;
+use strum::IntoEnumIterator;
+
+/// A validated external address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalAddress {
+  Bitcoin(BitcoinAddress),
+  Evm(EvmAddress),
+  Substrate(AccountId),
+}
+
+/// Script a Bitcoin address pays to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BitcoinAddress {
+  /// Pay to public key hash, base58 encoded.
+  P2pkh([u8; 20]),
+  /// Pay to script hash, base58 encoded.
+  P2sh([u8; 20]),
+  /// Segregated witness program, bech32 encoded for version 0 and bech32m for later versions.
+  Witness { version: u8, program: Vec<u8> },
+}
+
+/// Error returned when an external address is invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressError {
+  /// The address isn't UTF-8 text.
+  InvalidUtf8,
+  /// The address isn't valid base58, bech32, hex or SS58.
+  InvalidEncoding,
+  /// The address checksum doesn't match.
+  InvalidChecksum,
+  /// The decoded address has an invalid length.
+  InvalidLength,
+  /// The witness version or program of a segwit address is invalid.
+  InvalidWitnessProgram,
+  /// The address is valid but belongs to another network.
+  WrongNetwork,
+}
+
+impl std::fmt::Display for AddressError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidUtf8 => write!(f, "address isn't valid UTF-8"),
+      Self::InvalidEncoding => write!(f, "invalid address encoding"),
+      Self::InvalidChecksum => write!(f, "invalid address checksum"),
+      Self::InvalidLength => write!(f, "invalid address length"),
+      Self::InvalidWitnessProgram => write!(f, "invalid witness program"),
+      Self::WrongNetwork => write!(f, "address belongs to another network"),
+    }
+  }
+}
+
+impl std::error::Error for AddressError {}
+
+impl Asset {
+  /// Validate an address funds of the asset can be withdrawn to on the network, following
+  /// the algo of the base chain for wrapped assets:
+  /// - `SECP256K1`: Bitcoin base58 or bech32/bech32m address,
+  /// - `WEB3`: EVM hex address, its EIP-55 checksum is verified when it mixes cases,
+  /// - `SR25519`: SS58 address with the prefix of the network.
+  pub fn validate_external_address(
+    &self,
+    network: &Network,
+    address: &[u8],
+  ) -> Result<ExternalAddress, AddressError> {
+    let address = std::str::from_utf8(address).map_err(|_| AddressError::InvalidUtf8)?;
+    let algo = self.base_chain().unwrap_or_else(|| self.clone()).algo();
+    match algo {
+      Algo::SECP256K1 => parse_bitcoin_address(network, address).map(ExternalAddress::Bitcoin),
+      Algo::WEB3 => address
+        .parse::<EvmAddress>()
+        .map(ExternalAddress::Evm)
+        .map_err(|error| match error {
+          crate::evm::EvmAddressError::InvalidLength => AddressError::InvalidLength,
+          crate::evm::EvmAddressError::InvalidCharacter => AddressError::InvalidEncoding,
+          crate::evm::EvmAddressError::InvalidChecksum => AddressError::InvalidChecksum,
+        }),
+      Algo::SR25519 => network
+        .decode_account(address)
+        .map(ExternalAddress::Substrate)
+        .map_err(|error| match error {
+          crate::networks::Ss58Error::Invalid => AddressError::InvalidEncoding,
+          crate::networks::Ss58Error::UnexpectedPrefix { .. } => AddressError::WrongNetwork,
+        }),
+    }
+  }
+}
+
+fn parse_bitcoin_address(network: &Network, address: &str) -> Result<BitcoinAddress, AddressError> {
+  let bitcoin_network = network.bitcoin_network();
+
+  let lowercase = address.to_ascii_lowercase();
+  if BitcoinNetwork::iter()
+    .any(|network| lowercase.starts_with(&format!("{}1", network.bech32_hrp())))
+  {
+    // the human readable part ends at the last `1`, compare all of it
+    let (hrp, version, program) = bech32::decode_segwit(address)?;
+    if hrp == bitcoin_network.bech32_hrp() {
+      return Ok(BitcoinAddress::Witness { version, program });
+    }
+    if BitcoinNetwork::iter().any(|network| hrp == network.bech32_hrp()) {
+      return Err(AddressError::WrongNetwork);
+    }
+    return Err(AddressError::InvalidEncoding);
+  }
+
+  let data = bs58::decode(address)
+    .into_vec()
+    .map_err(|_| AddressError::InvalidEncoding)?;
+  if data.len() != 25 {
+    return Err(AddressError::InvalidLength);
+  }
+  let (payload, checksum) = data.split_at(21);
+  if sha2_256(&sha2_256(payload))[..4] != checksum[..] {
+    return Err(AddressError::InvalidChecksum);
+  }
+  let mut hash = [0; 20];
+  hash.copy_from_slice(&payload[1..]);
+  match payload[0] {
+    version if version == bitcoin_network.p2pkh_version() => Ok(BitcoinAddress::P2pkh(hash)),
+    version if version == bitcoin_network.p2sh_version() => Ok(BitcoinAddress::P2sh(hash)),
+    version
+      if BitcoinNetwork::iter()
+        .any(|network| version == network.p2pkh_version() || version == network.p2sh_version()) =>
+    {
+      Err(AddressError::WrongNetwork)
+    }
+    _ => Err(AddressError::InvalidEncoding),
+  }
+}
+
+/// Segwit addresses, see BIP-173 and BIP-350.
+mod bech32 {
+  use super::AddressError;
+
+  const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+  const BECH32_CONST: u32 = 1;
+  const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+  fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [
+      0x3b6a_57b2,
+      0x2650_8e6d,
+      0x1ea1_19fa,
+      0x3d42_33dd,
+      0x2a14_62b3,
+    ];
+    values.fold(1, |checksum, value| {
+      let top = checksum >> 25;
+      let checksum = (checksum & 0x1ff_ffff) << 5 ^ u32::from(value);
+      GENERATOR
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| top >> index & 1 == 1)
+        .fold(checksum, |checksum, (_, generator)| checksum ^ generator)
+    })
+  }
+
+  /// Regroup bits, e.g.: 5-bit groups to bytes, the padding must be zeros and shorter than a group.
+  fn convert_bits(data: &[u8], from: u32, to: u32) -> Option<Vec<u8>> {
+    let mut accumulator = 0_u32;
+    let mut bits = 0;
+    let mut converted = Vec::new();
+    for value in data {
+      accumulator = accumulator << from | u32::from(*value);
+      bits += from;
+      while bits >= to {
+        bits -= to;
+        converted.push((accumulator >> bits & ((1 << to) - 1)) as u8);
+      }
+    }
+    (bits < from && accumulator & ((1 << bits) - 1) == 0).then_some(converted)
+  }
+
+  /// Decode a segwit address into its lowercase human readable part, witness version and program.
+  pub fn decode_segwit(address: &str) -> Result<(String, u8, Vec<u8>), AddressError> {
+    let has_lowercase = address.bytes().any(|c| c.is_ascii_lowercase());
+    let has_uppercase = address.bytes().any(|c| c.is_ascii_uppercase());
+    if address.len() > 90 || (has_lowercase && has_uppercase) {
+      return Err(AddressError::InvalidEncoding);
+    }
+    let address = address.to_ascii_lowercase();
+    let (hrp, data) = address
+      .rsplit_once('1')
+      .ok_or(AddressError::InvalidEncoding)?;
+    if data.len() < 7 {
+      return Err(AddressError::InvalidLength);
+    }
+    let data = data
+      .bytes()
+      .map(|c| {
+        CHARSET
+          .iter()
+          .position(|&digit| digit == c)
+          .map(|value| value as u8)
+      })
+      .collect::<Option<Vec<_>>>()
+      .ok_or(AddressError::InvalidEncoding)?;
+
+    let expanded = hrp
+      .bytes()
+      .map(|c| c >> 5)
+      .chain([0])
+      .chain(hrp.bytes().map(|c| c & 0x1f));
+    let constant = polymod(expanded.chain(data.iter().copied()));
+
+    let (version, program) = (data[0], &data[1..data.len() - 6]);
+    let expected = if version == 0 {
+      BECH32_CONST
+    } else {
+      BECH32M_CONST
+    };
+    if constant != expected {
+      return Err(AddressError::InvalidChecksum);
+    }
+    let program = convert_bits(program, 5, 8).ok_or(AddressError::InvalidWitnessProgram)?;
+    let valid = match version {
+      0 => program.len() == 20 || program.len() == 32,
+      1..=16 => (2..=40).contains(&program.len()),
+      _ => false,
+    };
+    if !valid {
+      return Err(AddressError::InvalidWitnessProgram);
+    }
+    Ok((hrp.to_string(), version, program))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn validate(
+    asset: Asset,
+    network: &Network,
+    address: &str,
+  ) -> Result<ExternalAddress, AddressError> {
+    asset.validate_external_address(network, address.as_bytes())
+  }
+
+  #[test]
+  fn test_bitcoin_address() {
+    let mainnet = |address| validate(Asset::Bitcoin, &Network::Mainnet, address);
+    assert!(matches!(
+      mainnet("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+      Ok(ExternalAddress::Bitcoin(BitcoinAddress::P2pkh(_)))
+    ));
+    assert!(matches!(
+      mainnet("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"),
+      Ok(ExternalAddress::Bitcoin(BitcoinAddress::P2sh(_)))
+    ));
+    assert!(matches!(
+      mainnet("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ"),
+      Ok(ExternalAddress::Bitcoin(BitcoinAddress::Witness {
+        version: 0,
+        ..
+      }))
+    ));
+    assert!(matches!(
+      mainnet("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"),
+      Ok(ExternalAddress::Bitcoin(BitcoinAddress::Witness {
+        version: 1,
+        ..
+      }))
+    ));
+
+    assert_eq!(
+      mainnet("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+      Err(AddressError::InvalidChecksum)
+    );
+    assert_eq!(
+      mainnet("1A1zP1eP5QGefi2DMPTfTL5SLmv7Divf"),
+      Err(AddressError::InvalidLength)
+    );
+    assert_eq!(
+      mainnet("0A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+      Err(AddressError::InvalidEncoding)
+    );
+    assert_eq!(
+      mainnet("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdp"),
+      Err(AddressError::InvalidChecksum)
+    );
+    // mixed case
+    assert_eq!(
+      mainnet("bc1qAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ"),
+      Err(AddressError::InvalidEncoding)
+    );
+    // valid checksum for the `bc1xyz` human readable part
+    assert_eq!(
+      mainnet("bc1xyz1qw508d6qejxtdg4y5r3zarvary0c5xw7kvq7e48"),
+      Err(AddressError::InvalidEncoding)
+    );
+    assert!(mainnet("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_ok());
+
+    // testnet addresses
+    let testnet = "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7";
+    assert_eq!(mainnet(testnet), Err(AddressError::WrongNetwork));
+    assert!(validate(Asset::Bitcoin, &Network::Testnet, testnet).is_ok());
+    assert_eq!(
+      validate(Asset::Bitcoin, &Network::Local, testnet),
+      Err(AddressError::WrongNetwork)
+    );
+    assert_eq!(
+      validate(
+        Asset::Bitcoin,
+        &Network::Testnet,
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+      ),
+      Err(AddressError::WrongNetwork)
+    );
+  }
+
+  #[test]
+  fn test_evm_address() {
+    let address = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+    let expected = Ok(ExternalAddress::Evm(address.parse().unwrap()));
+    assert_eq!(
+      validate(Asset::Ethereum, &Network::Mainnet, address),
+      expected
+    );
+    // wrapped assets follow their base chain
+    assert_eq!(
+      validate(Asset::Tether, &Network::Mainnet, address),
+      expected
+    );
+    assert_eq!(
+      validate(Asset::USDCoin, &Network::Mainnet, &address.to_lowercase()),
+      expected
+    );
+    assert_eq!(
+      validate(
+        Asset::Ethereum,
+        &Network::Mainnet,
+        "0xdAC17F958D2ee523a2206206994597C13D831eC7"
+      ),
+      Err(AddressError::InvalidChecksum)
+    );
+    assert_eq!(
+      validate(
+        Asset::Ethereum,
+        &Network::Mainnet,
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+      ),
+      Err(AddressError::InvalidLength)
+    );
+    assert_eq!(
+      Asset::AllTimeHigh.validate_external_address(&Network::Mainnet, &[0xff; 42]),
+      Err(AddressError::InvalidUtf8)
+    );
+  }
+
+  #[test]
+  fn test_substrate_address() {
+    let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    assert!(matches!(
+      validate(Asset::Tdfy, &Network::Testnet, alice),
+      Ok(ExternalAddress::Substrate(_))
+    ));
+    assert_eq!(
+      validate(Asset::Tdfy, &Network::Mainnet, alice),
+      Err(AddressError::WrongNetwork)
+    );
+    assert_eq!(
+      validate(
+        Asset::Tdfy,
+        &Network::Testnet,
+        "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+      ),
+      Err(AddressError::InvalidEncoding)
+    );
+  }
+}
@@ -0,0 +1,829 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion between balances in base units and human readable decimal amounts, e.g.:
+//! `1_500_000` satoshis and `0.015 BTC`, and `AssetAmount`, a balance bound to its currency.
+
+use crate::{
+  assets::{AssetInfo, Denomination},
+  Amount, Balance, CurrencyId, Fee, Mint, Swap, Withdrawal,
+};
+use codec::{alloc::string::String, Decode, Encode, MaxEncodedLen};
+use core::{cmp::Ordering, fmt::Write};
+use scale_info::TypeInfo;
+use sp_arithmetic::FixedPointNumber;
+use sp_core::U256;
+use sp_runtime::{FixedU128, RuntimeDebug};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Rounding applied when a balance loses precision.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Rounding {
+  /// Round toward zero, i.e. truncate.
+  Floor,
+  /// Round away from zero.
+  Ceil,
+  /// Round to the nearest, ties to the even neighbour (banker's rounding).
+  HalfEven,
+}
+
+impl Rounding {
+  /// Divide `amount` by `divisor`, rounding the quotient.
+  ///
+  /// # Panics
+  ///
+  /// If `divisor` is zero.
+  pub fn divide(&self, amount: Balance, divisor: Balance) -> Balance {
+    let (quotient, remainder) = (amount / divisor, amount % divisor);
+    let round_up = match self {
+      Rounding::Floor => false,
+      Rounding::Ceil => remainder > 0,
+      Rounding::HalfEven => {
+        let rest = divisor - remainder;
+        remainder > rest || (remainder == rest && quotient % 2 == 1)
+      }
+    };
+    // a remainder implies `divisor > 1`, so the quotient can't be `Balance::MAX`
+    quotient + Balance::from(round_up)
+  }
+}
+
+/// Options of `format_amount`, the default is the full precision of the asset, without trailing
+/// zeros, separator, prefix nor symbol.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct FormatOptions {
+  /// Maximum number of decimals displayed, all the asset decimals when `None`.
+  pub precision: Option<u8>,
+  /// Rounding of the decimals beyond `precision`.
+  pub rounding: Rounding,
+  /// Remove the trailing zeros of the decimals.
+  pub trim_zeros: bool,
+  /// Separator inserted between groups of thousands, e.g. `,`.
+  pub thousands_separator: Option<char>,
+  /// Prepend the asset prefix, e.g. `₿`.
+  pub prefix: bool,
+  /// Append the asset symbol, e.g. `BTC`.
+  pub symbol: bool,
+}
+
+impl Default for FormatOptions {
+  fn default() -> Self {
+    FormatOptions {
+      precision: None,
+      rounding: Rounding::Floor,
+      trim_zeros: true,
+      thousands_separator: None,
+      prefix: false,
+      symbol: false,
+    }
+  }
+}
+
+/// Error returned when a string isn't a valid amount of an asset.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AmountError {
+  /// The string is not a decimal number.
+  Invalid,
+  /// The amount has more decimals than the asset.
+  ExcessPrecision { decimals: u8 },
+  /// The amount, in base units, is greater than `Balance::MAX`.
+  Overflow,
+  /// The amount is denominated in another asset.
+  UnexpectedSymbol,
+}
+
+impl core::fmt::Display for AmountError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::Invalid => write!(f, "invalid amount"),
+      Self::ExcessPrecision { decimals } => {
+        write!(f, "amount has more than {} decimals", decimals)
+      }
+      Self::Overflow => write!(f, "amount overflows `Balance`"),
+      Self::UnexpectedSymbol => write!(f, "amount is denominated in another asset"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AmountError {}
+
+/// Error returned when converting an amount between denominations of an asset.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DenominationError {
+  /// The asset has no denomination with this name.
+  UnknownDenomination,
+  /// The converted amount is greater than `Balance::MAX`.
+  Overflow,
+  /// The amount can't be represented exactly in the target denomination.
+  PrecisionLoss,
+}
+
+impl core::fmt::Display for DenominationError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::UnknownDenomination => write!(f, "unknown denomination"),
+      Self::Overflow => write!(f, "amount overflows `Balance`"),
+      Self::PrecisionLoss => write!(f, "amount can't be converted without loss of precision"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DenominationError {}
+
+/// Error returned when scaling an amount overflows.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct ArithmeticOverflow;
+
+impl core::fmt::Display for ArithmeticOverflow {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "arithmetic overflow")
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ArithmeticOverflow {}
+
+/// Append the decimal `digits` to `amount`.
+fn push_digits(amount: Balance, digits: &str) -> Result<Balance, AmountError> {
+  digits.bytes().try_fold(amount, |amount, digit| {
+    if !digit.is_ascii_digit() {
+      return Err(AmountError::Invalid);
+    }
+    amount
+      .checked_mul(10)
+      .and_then(|amount| amount.checked_add((digit - b'0').into()))
+      .ok_or(AmountError::Overflow)
+  })
+}
+
+/// Parse the integer part of an amount, optionally grouped by thousands with `,` or `_`.
+fn parse_integer(integer: &str) -> Result<Balance, AmountError> {
+  match integer.chars().find(|c| *c == ',' || *c == '_') {
+    Some(separator) => {
+      integer
+        .split(separator)
+        .enumerate()
+        .try_fold(0, |amount, (index, group)| match group.len() {
+          1..=3 if index == 0 => push_digits(amount, group),
+          3 if index > 0 => push_digits(amount, group),
+          _ => Err(AmountError::Invalid),
+        })
+    }
+    None if integer.is_empty() => Err(AmountError::Invalid),
+    None => push_digits(0, integer),
+  }
+}
+
+impl<A> AssetInfo<A> {
+  /// Parse a decimal amount of the asset into base units, e.g.: `"0.015 BTC"` to `1_500_000`.
+  ///
+  /// The amount may start with the asset prefix, end with its symbol or an alias, and group its
+  /// integer part by thousands with `,` or `_`. Decimals beyond the asset precision are rejected,
+  /// unless they are zeros.
+  pub fn parse_amount(&self, amount: &str) -> Result<Balance, AmountError> {
+    let mut amount = amount.trim();
+    if let Some(prefix) = self.prefix {
+      amount = amount.strip_prefix(prefix).unwrap_or(amount).trim_start();
+    }
+
+    let number = amount.trim_end_matches(char::is_alphabetic);
+    let unit = &amount[number.len()..];
+    if !unit.is_empty()
+      && !self.is_symbol(unit)
+      && !self
+        .aliases
+        .iter()
+        .any(|alias| alias.eq_ignore_ascii_case(unit))
+    {
+      return Err(AmountError::UnexpectedSymbol);
+    }
+
+    let (integer, fraction) = match number.trim_end().split_once('.') {
+      Some((_, "")) => return Err(AmountError::Invalid),
+      Some((integer, fraction)) => (integer, fraction.trim_end_matches('0')),
+      None => (number.trim_end(), ""),
+    };
+    if fraction.len() > self.decimals.into() {
+      return Err(AmountError::ExcessPrecision {
+        decimals: self.decimals,
+      });
+    }
+
+    let integer = parse_integer(integer)?;
+    let padding = usize::from(self.decimals) - fraction.len();
+    let amount = push_digits(integer, fraction)?;
+    (0..padding).try_fold(amount, |amount, _| {
+      amount.checked_mul(10).ok_or(AmountError::Overflow)
+    })
+  }
+
+  /// Base units in one unit of the asset, e.g. `100_000_000` for `BTC`.
+  fn one_unit(&self) -> Result<Balance, ArithmeticOverflow> {
+    10_u128
+      .checked_pow(self.decimals.into())
+      .ok_or(ArithmeticOverflow)
+  }
+
+  /// Convert a whole number of units of the asset to base units, e.g.: `10` BTC to
+  /// `1_000_000_000` satoshis, failing on overflow.
+  pub fn checked_mul(&self, amount: Balance) -> Result<Balance, ArithmeticOverflow> {
+    amount
+      .checked_mul(self.one_unit()?)
+      .ok_or(ArithmeticOverflow)
+  }
+
+  /// Convert an amount in base units to a fixed point number of units of the asset, e.g.:
+  /// `150_000_000` satoshis to `1.5`, failing on overflow. Decimals beyond the 18 of
+  /// `FixedU128` are truncated.
+  pub fn checked_to_fixed(&self, amount: Balance) -> Result<FixedU128, ArithmeticOverflow> {
+    FixedU128::checked_from_rational(amount, self.one_unit()?).ok_or(ArithmeticOverflow)
+  }
+
+  /// Convert a fixed point number of units of the asset to base units, e.g.: `1.5` BTC to
+  /// `150_000_000` satoshis, failing on overflow. Decimals beyond the asset ones are truncated.
+  pub fn checked_from_fixed(&self, value: FixedU128) -> Result<Balance, ArithmeticOverflow> {
+    value
+      .checked_mul_int(self.one_unit()?)
+      .ok_or(ArithmeticOverflow)
+  }
+
+  /// Find a denomination of the asset by its name, e.g. `gwei`.
+  pub fn denomination(&self, name: &str) -> Option<&'static Denomination> {
+    self
+      .denominations
+      .iter()
+      .find(|denomination| denomination.name == name)
+  }
+
+  /// Convert `amount` from the denomination `from` to the denomination `to`, e.g. from `gwei` to
+  /// `wei`. Conversions which would lose precision are rejected.
+  pub fn convert(
+    &self,
+    amount: Balance,
+    from: &str,
+    to: &str,
+  ) -> Result<Balance, DenominationError> {
+    let from = self
+      .denomination(from)
+      .ok_or(DenominationError::UnknownDenomination)?;
+    let to = self
+      .denomination(to)
+      .ok_or(DenominationError::UnknownDenomination)?;
+
+    match from.exponent.cmp(&to.exponent) {
+      Ordering::Greater => amount
+        .checked_mul(10_u128.pow((from.exponent - to.exponent).into()))
+        .ok_or(DenominationError::Overflow),
+      _ => {
+        let divisor = 10_u128.pow((to.exponent - from.exponent).into());
+        match amount % divisor {
+          0 => Ok(amount / divisor),
+          _ => Err(DenominationError::PrecisionLoss),
+        }
+      }
+    }
+  }
+
+  /// Convert `amount` to the decimals of `other`, e.g. from `ETH` (18 decimals) to `USDT`
+  /// (6 decimals), rounding with `rounding` when decimals are lost.
+  ///
+  /// Returns the rescaled amount and the dust, in base units of this asset: positive when lost by
+  /// rounding down, negative when added by rounding up. `None` if the rescaled amount overflows.
+  pub fn rescale_to<B>(
+    &self,
+    other: &AssetInfo<B>,
+    amount: Balance,
+    rounding: Rounding,
+  ) -> Option<(Balance, Amount)> {
+    match self.decimals.cmp(&other.decimals) {
+      Ordering::Greater => {
+        // at most `10^38`, which fits in an `Amount`
+        let divisor = 10_u128.pow((self.decimals - other.decimals).into());
+        let rescaled = rounding.divide(amount, divisor);
+        let remainder = (amount % divisor) as Amount;
+        let dust = match rescaled > amount / divisor {
+          true => remainder - divisor as Amount,
+          false => remainder,
+        };
+        Some((rescaled, dust))
+      }
+      _ => amount
+        .checked_mul(10_u128.pow((other.decimals - self.decimals).into()))
+        .map(|rescaled| (rescaled, 0)),
+    }
+  }
+
+  /// Format an amount in base units as a decimal amount of the asset, e.g.: `1_500_000` to
+  /// `0.015 BTC` with the symbol.
+  pub fn format_amount(&self, amount: Balance, options: FormatOptions) -> String {
+    let precision = options
+      .precision
+      .map_or(self.decimals, |precision| precision.min(self.decimals));
+    let amount = options
+      .rounding
+      .divide(amount, 10_u128.pow((self.decimals - precision).into()));
+    let scale = 10_u128.pow(precision.into());
+
+    let mut output = String::new();
+    if let (true, Some(prefix)) = (options.prefix, self.prefix) {
+      output.push_str(prefix);
+    }
+
+    let mut integer = String::new();
+    let _ = write!(integer, "{}", amount / scale);
+    for (index, group) in integer.as_bytes().rchunks(3).rev().enumerate() {
+      if let (true, Some(separator)) = (index > 0, options.thousands_separator) {
+        output.push(separator);
+      }
+      // only ASCII digits
+      output.push_str(core::str::from_utf8(group).unwrap_or_default());
+    }
+
+    let mut fraction = String::new();
+    let _ = write!(
+      fraction,
+      "{:0width$}",
+      amount % scale,
+      width = usize::from(precision)
+    );
+    let fraction = match options.trim_zeros {
+      true => fraction.trim_end_matches('0'),
+      false => &fraction,
+    };
+    if precision > 0 && !fraction.is_empty() {
+      output.push('.');
+      output.push_str(fraction);
+    }
+
+    if options.symbol {
+      output.push(' ');
+      output.push_str(self.symbol);
+    }
+    output
+  }
+}
+
+/// A balance of a currency, which can't be mixed with a balance of another currency.
+#[derive(
+  Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen, Copy, Clone, RuntimeDebug, Default,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Hash))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetAmount {
+  /// The currency of the amount.
+  pub currency: CurrencyId,
+  /// The amount in base units of the currency.
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
+  pub amount: Balance,
+}
+
+/// Error returned by the checked operations of `AssetAmount`.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AssetAmountError {
+  /// The operands are amounts of different currencies.
+  CurrencyMismatch {
+    expected: CurrencyId,
+    found: CurrencyId,
+  },
+  /// The result is out of the `Balance` bounds.
+  Overflow,
+  /// The ratio has a zero denominator.
+  DivisionByZero,
+}
+
+impl core::fmt::Display for AssetAmountError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::CurrencyMismatch { expected, found } => write!(
+        f,
+        "currency mismatch, expected {:?} found {:?}",
+        expected, found
+      ),
+      Self::Overflow => write!(f, "amount overflows `Balance`"),
+      Self::DivisionByZero => write!(f, "division by zero"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AssetAmountError {}
+
+impl AssetAmount {
+  pub const fn new(currency: CurrencyId, amount: Balance) -> Self {
+    AssetAmount { currency, amount }
+  }
+
+  /// No amount of `currency`.
+  pub const fn zero(currency: CurrencyId) -> Self {
+    AssetAmount::new(currency, 0)
+  }
+
+  pub const fn is_zero(&self) -> bool {
+    self.amount == 0
+  }
+
+  fn same_currency(&self, other: &AssetAmount) -> Result<(), AssetAmountError> {
+    match self.currency == other.currency {
+      true => Ok(()),
+      false => Err(AssetAmountError::CurrencyMismatch {
+        expected: self.currency,
+        found: other.currency,
+      }),
+    }
+  }
+
+  /// Checked addition, fails if `other` is another currency or on overflow.
+  pub fn checked_add(&self, other: AssetAmount) -> Result<AssetAmount, AssetAmountError> {
+    self.same_currency(&other)?;
+    let amount = self
+      .amount
+      .checked_add(other.amount)
+      .ok_or(AssetAmountError::Overflow)?;
+    Ok(AssetAmount::new(self.currency, amount))
+  }
+
+  /// Checked subtraction, fails if `other` is another currency or greater than `self`.
+  pub fn checked_sub(&self, other: AssetAmount) -> Result<AssetAmount, AssetAmountError> {
+    self.same_currency(&other)?;
+    let amount = self
+      .amount
+      .checked_sub(other.amount)
+      .ok_or(AssetAmountError::Overflow)?;
+    Ok(AssetAmount::new(self.currency, amount))
+  }
+
+  /// Multiply by `numerator / denominator` without intermediate overflow, e.g. to apply a share
+  /// or a price with the same currency.
+  pub fn checked_mul_ratio(
+    &self,
+    numerator: Balance,
+    denominator: Balance,
+    rounding: Rounding,
+  ) -> Result<AssetAmount, AssetAmountError> {
+    if denominator == 0 {
+      return Err(AssetAmountError::DivisionByZero);
+    }
+    let product = U256::from(self.amount) * U256::from(numerator);
+    let denominator = U256::from(denominator);
+    let (quotient, remainder) = product.div_mod(denominator);
+    let round_up = match rounding {
+      Rounding::Floor => false,
+      Rounding::Ceil => !remainder.is_zero(),
+      Rounding::HalfEven => {
+        let rest = denominator - remainder;
+        remainder > rest || (remainder == rest && quotient.bit(0))
+      }
+    };
+    let amount = quotient + U256::from(u8::from(round_up));
+    match amount > U256::from(Balance::MAX) {
+      true => Err(AssetAmountError::Overflow),
+      false => Ok(AssetAmount::new(self.currency, amount.low_u128())),
+    }
+  }
+}
+
+impl From<(CurrencyId, Balance)> for AssetAmount {
+  fn from((currency, amount): (CurrencyId, Balance)) -> Self {
+    AssetAmount::new(currency, amount)
+  }
+}
+
+impl From<AssetAmount> for (CurrencyId, Balance) {
+  fn from(asset_amount: AssetAmount) -> Self {
+    (asset_amount.currency, asset_amount.amount)
+  }
+}
+
+impl<AccountId, BlockNumber> Swap<AccountId, BlockNumber> {
+  /// Amount offered by the swap.
+  pub fn asset_amount_from(&self) -> AssetAmount {
+    AssetAmount::new(self.token_from, self.amount_from)
+  }
+
+  /// Amount requested by the swap.
+  pub fn asset_amount_to(&self) -> AssetAmount {
+    AssetAmount::new(self.token_to, self.amount_to)
+  }
+}
+
+impl Fee {
+  /// Fee, in the `currency` of the transaction.
+  pub fn fee_amount(&self, currency: CurrencyId) -> AssetAmount {
+    AssetAmount::new(currency, self.fee)
+  }
+
+  /// Fee, in TDFY's.
+  pub fn fee_tdfy_amount(&self) -> AssetAmount {
+    AssetAmount::new(CurrencyId::Tdfy, self.fee_tdfy)
+  }
+}
+
+impl<AccountId, BlockNumber, BoundedString> Withdrawal<AccountId, BlockNumber, BoundedString> {
+  /// Amount to withdraw.
+  pub fn asset_amount(&self) -> AssetAmount {
+    AssetAmount::new(self.asset_id, self.amount)
+  }
+}
+
+impl<AccountId, BoundedString> Mint<AccountId, BoundedString> {
+  /// Amount to mint.
+  pub fn asset_amount(&self) -> AssetAmount {
+    AssetAmount::new(self.currency_id, self.mint_amount)
+  }
+
+  /// Amount paid in gas fee for the deposit.
+  pub fn gas_asset_amount(&self) -> Option<AssetAmount> {
+    self
+      .gas_amount
+      .map(|gas_amount| AssetAmount::new(self.currency_id, gas_amount))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::assets::Asset;
+  use codec::{Decode, Encode};
+
+  #[test]
+  fn test_rounding() {
+    assert_eq!(Rounding::Floor.divide(19, 10), 1);
+    assert_eq!(Rounding::Ceil.divide(11, 10), 2);
+    assert_eq!(Rounding::Ceil.divide(10, 10), 1);
+    assert_eq!(Rounding::HalfEven.divide(15, 10), 2);
+    assert_eq!(Rounding::HalfEven.divide(25, 10), 2);
+    assert_eq!(Rounding::HalfEven.divide(26, 10), 3);
+    assert_eq!(Rounding::HalfEven.divide(Balance::MAX, 1), Balance::MAX);
+  }
+
+  #[test]
+  fn test_parse_amount() {
+    let btc = Asset::Bitcoin;
+    assert_eq!(btc.parse_amount("0.015"), Ok(1_500_000));
+    assert_eq!(btc.parse_amount(" 0.015 BTC "), Ok(1_500_000));
+    assert_eq!(btc.parse_amount("0.015btc"), Ok(1_500_000));
+    assert_eq!(btc.parse_amount("₿ 1,000.5 XBT"), Ok(100_050_000_000));
+    assert_eq!(btc.parse_amount("1_000"), Ok(100_000_000_000));
+    assert_eq!(btc.parse_amount("0.123456780"), Ok(12_345_678));
+    assert_eq!(Asset::Tdfy.parse_amount("12"), Ok(12_000_000_000_000));
+
+    assert_eq!(
+      btc.parse_amount("0.123456789"),
+      Err(AmountError::ExcessPrecision { decimals: 8 })
+    );
+    assert_eq!(
+      btc.parse_amount("1 ETH"),
+      Err(AmountError::UnexpectedSymbol)
+    );
+    assert_eq!(
+      Asset::Ethereum.parse_amount("340282366920938463463.374607431768211456"),
+      Err(AmountError::Overflow)
+    );
+    for invalid in [
+      "",
+      "BTC",
+      ".5",
+      "1.",
+      "-1",
+      "1e3",
+      "1.2.3",
+      "10,00",
+      "1,000_000",
+      "₿",
+    ] {
+      assert_eq!(
+        btc.parse_amount(invalid),
+        Err(AmountError::Invalid),
+        "{}",
+        invalid
+      );
+    }
+  }
+
+  #[test]
+  fn test_format_amount() {
+    let btc = Asset::Bitcoin;
+    assert_eq!(
+      btc.format_amount(1_500_000, FormatOptions::default()),
+      "0.015"
+    );
+    assert_eq!(btc.format_amount(0, FormatOptions::default()), "0");
+    assert_eq!(
+      btc.format_amount(
+        123_456_789_012,
+        FormatOptions {
+          thousands_separator: Some(','),
+          prefix: true,
+          symbol: true,
+          ..Default::default()
+        }
+      ),
+      "₿1,234.56789012 BTC"
+    );
+    assert_eq!(
+      btc.format_amount(
+        1_500_000,
+        FormatOptions {
+          trim_zeros: false,
+          ..Default::default()
+        }
+      ),
+      "0.01500000"
+    );
+
+    let rounded = |rounding| FormatOptions {
+      precision: Some(2),
+      rounding,
+      ..Default::default()
+    };
+    assert_eq!(
+      btc.format_amount(99_999_999, rounded(Rounding::Floor)),
+      "0.99"
+    );
+    assert_eq!(btc.format_amount(99_999_999, rounded(Rounding::Ceil)), "1");
+    assert_eq!(
+      btc.format_amount(1_500_000, rounded(Rounding::HalfEven)),
+      "0.02"
+    );
+    assert_eq!(
+      btc.format_amount(2_500_000, rounded(Rounding::HalfEven)),
+      "0.02"
+    );
+    assert_eq!(
+      btc.format_amount(2_500_001, rounded(Rounding::HalfEven)),
+      "0.03"
+    );
+
+    let amount = btc.format_amount(Balance::MAX, FormatOptions::default());
+    assert_eq!(btc.parse_amount(&amount), Ok(Balance::MAX));
+  }
+
+  #[test]
+  fn test_checked_scaling() {
+    let (btc, eth) = (Asset::Bitcoin, Asset::Ethereum);
+
+    assert_eq!(btc.checked_mul(10), Ok(1_000_000_000));
+    assert_eq!(eth.checked_mul(Balance::MAX), Err(ArithmeticOverflow));
+
+    let one_and_half = FixedU128::saturating_from_rational(3, 2);
+    assert_eq!(btc.checked_to_fixed(150_000_000), Ok(one_and_half));
+    assert_eq!(btc.checked_from_fixed(one_and_half), Ok(150_000_000));
+    assert_eq!(
+      eth.checked_from_fixed(one_and_half),
+      Ok(1_500_000_000_000_000_000)
+    );
+    // 1 satoshi is `0.00000001`, exactly represented with 18 decimals
+    assert_eq!(
+      btc.checked_from_fixed(btc.checked_to_fixed(1).unwrap()),
+      Ok(1)
+    );
+    // `FixedU128` can't represent more than ~3.4 * 10^20 units
+    assert_eq!(
+      Asset::Tether.checked_to_fixed(Balance::MAX),
+      Err(ArithmeticOverflow)
+    );
+    assert_eq!(
+      btc.checked_from_fixed(FixedU128::from_inner(u128::MAX)),
+      Ok(34_028_236_692_093_846_346_337_460_743)
+    );
+  }
+
+  #[test]
+  fn test_convert() {
+    let (btc, eth) = (Asset::Bitcoin, Asset::Ethereum);
+
+    let names = |asset: &Asset| {
+      asset
+        .info()
+        .denominations
+        .iter()
+        .map(|denomination| (denomination.name, denomination.exponent))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&btc), vec![("satoshi", 0), ("mBTC", 5), ("BTC", 8)]);
+    assert_eq!(
+      names(&eth),
+      vec![("wei", 0), ("gwei", 9), ("ether", 18), ("ETH", 18)]
+    );
+    assert_eq!(names(&Asset::Tether), vec![("USDT", 6)]);
+
+    assert_eq!(eth.convert(2, "gwei", "wei"), Ok(2_000_000_000));
+    assert_eq!(eth.convert(2_000_000_000, "wei", "gwei"), Ok(2));
+    assert_eq!(eth.convert(3, "ether", "ETH"), Ok(3));
+    assert_eq!(btc.convert(1, "BTC", "mBTC"), Ok(1_000));
+    assert_eq!(btc.convert(200_000, "satoshi", "mBTC"), Ok(2));
+    assert_eq!(
+      btc.convert(250_000, "satoshi", "mBTC"),
+      Err(DenominationError::PrecisionLoss)
+    );
+    assert_eq!(
+      eth.convert(Balance::MAX, "ETH", "wei"),
+      Err(DenominationError::Overflow)
+    );
+    assert_eq!(
+      btc.convert(1, "gwei", "satoshi"),
+      Err(DenominationError::UnknownDenomination)
+    );
+  }
+
+  #[test]
+  fn test_rescale_to() {
+    let (eth, usdt, usdc) = (Asset::Ethereum, Asset::Tether, Asset::USDCoin);
+
+    // 1.234567891 ETH
+    let amount = 1_234_567_891_000_000_000;
+    assert_eq!(
+      eth.rescale_to(&usdt, amount, Rounding::Floor),
+      Some((1_234_567, 891_000_000_000))
+    );
+    assert_eq!(
+      eth.rescale_to(&usdt, amount, Rounding::Ceil),
+      Some((1_234_568, -109_000_000_000))
+    );
+    assert_eq!(
+      eth.rescale_to(&usdt, amount, Rounding::HalfEven),
+      Some((1_234_568, -109_000_000_000))
+    );
+    assert_eq!(
+      eth.rescale_to(&usdt, 2_500_000_000_000, Rounding::HalfEven),
+      Some((2, 500_000_000_000))
+    );
+    assert_eq!(
+      eth.rescale_to(&usdt, 1_000_000_000_000, Rounding::Ceil),
+      Some((1, 0))
+    );
+
+    assert_eq!(
+      usdt.rescale_to(&eth, 1_234_568, Rounding::Floor),
+      Some((1_234_568_000_000_000_000, 0))
+    );
+    assert_eq!(usdt.rescale_to(&usdc, 42, Rounding::Ceil), Some((42, 0)));
+    assert_eq!(usdt.rescale_to(&eth, Balance::MAX, Rounding::Floor), None);
+  }
+
+  #[test]
+  fn test_asset_amount() {
+    let btc = |amount| AssetAmount::new(CurrencyId::Wrapped(2), amount);
+    let tdfy = AssetAmount::new(CurrencyId::Tdfy, 10);
+
+    assert_eq!(btc(1).checked_add(btc(2)), Ok(btc(3)));
+    assert_eq!(btc(3).checked_sub(btc(2)), Ok(btc(1)));
+    assert_eq!(btc(1).checked_sub(btc(2)), Err(AssetAmountError::Overflow));
+    assert_eq!(
+      btc(Balance::MAX).checked_add(btc(1)),
+      Err(AssetAmountError::Overflow)
+    );
+    assert_eq!(
+      btc(1).checked_add(tdfy),
+      Err(AssetAmountError::CurrencyMismatch {
+        expected: CurrencyId::Wrapped(2),
+        found: CurrencyId::Tdfy,
+      })
+    );
+
+    assert_eq!(btc(10).checked_mul_ratio(1, 4, Rounding::Floor), Ok(btc(2)));
+    assert_eq!(btc(10).checked_mul_ratio(1, 4, Rounding::Ceil), Ok(btc(3)));
+    assert_eq!(
+      btc(10).checked_mul_ratio(1, 4, Rounding::HalfEven),
+      Ok(btc(2))
+    );
+    assert_eq!(
+      btc(Balance::MAX).checked_mul_ratio(Balance::MAX, Balance::MAX, Rounding::Floor),
+      Ok(btc(Balance::MAX))
+    );
+    assert_eq!(
+      btc(Balance::MAX).checked_mul_ratio(2, 1, Rounding::Floor),
+      Err(AssetAmountError::Overflow)
+    );
+    assert_eq!(
+      btc(1).checked_mul_ratio(1, 0, Rounding::Floor),
+      Err(AssetAmountError::DivisionByZero)
+    );
+
+    let (currency, amount) = btc(5).into();
+    assert_eq!(AssetAmount::from((currency, amount)), btc(5));
+    assert_eq!(AssetAmount::decode(&mut &btc(5).encode()[..]), Ok(btc(5)));
+    #[cfg(feature = "std")]
+    assert_eq!(
+      serde_json::from_str::<AssetAmount>(&serde_json::to_string(&btc(5)).unwrap()).unwrap(),
+      btc(5)
+    );
+  }
+}
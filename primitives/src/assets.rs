@@ -14,34 +14,183 @@
 // You should have received a copy of the GNU General Public License
 // along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{AssetId, Balance, CurrencyId};
+use crate::{
+  amount::{AmountError, ArithmeticOverflow, DenominationError, FormatOptions, Rounding},
+  Amount, AssetId, Balance, CurrencyId,
+};
 use codec::alloc::string::{String, ToString};
 #[cfg(not(feature = "std"))]
 use sp_arithmetic::traits::Saturating;
+use sp_runtime::{FixedU128, RuntimeDebug};
 
 use tidefi_primitives_macro::assets;
 
 #[cfg(feature = "std")]
 use {
+  crate::{
+    evm::EvmAddress,
+    networks::{Addresses, ChainIds, Enabled, Network, SchedulePoint, Schedules},
+  },
   serde::{Deserialize, Serialize},
-  strum_macros::EnumIter,
+  std::collections::BTreeMap,
 };
 
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
 pub enum Algo {
   SR25519,
   SECP256K1,
   WEB3,
 }
 
+/// Lifecycle of an asset in the registry.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(tag = "status", rename_all = "camelCase"))]
+pub enum Lifecycle {
+  /// Listed and fully operational.
+  Listed,
+  /// Still operational, but should not be used anymore.
+  Deprecated {
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    since: Option<&'static str>,
+  },
+  /// Not operational anymore, the asset is kept to decode historical data.
+  Delisted,
+}
+
+impl Lifecycle {
+  /// Whether the asset is still operational (listed or deprecated).
+  pub const fn is_active(&self) -> bool {
+    !matches!(self, Lifecycle::Delisted)
+  }
+}
+
+/// Denomination of an asset, e.g.: `gwei` is `10^9` wei.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct Denomination {
+  /// Name, e.g.: `gwei`.
+  pub name: &'static str,
+  /// Exponent relative to the base unit, e.g.: `9` for `gwei`.
+  pub exponent: u8,
+}
+
+/// Static metadata of an asset, as declared in the `#[assets]` registry.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct AssetInfo<A = Asset> {
+  /// The asset described.
+  pub asset: A,
+  /// The `AssetId` used on-chain.
+  pub id: AssetId,
+  /// Symbol, e.g.: `BTC`.
+  pub symbol: &'static str,
+  /// Name, e.g.: `Bitcoin`.
+  pub name: &'static str,
+  /// Number of decimals, e.g.: `8` for `BTC`.
+  pub decimals: u8,
+  /// Algorythm of the coin.
+  pub algo: Algo,
+  /// Units name, e.g.: `satoshi`.
+  pub unit_name: Option<&'static str>,
+  /// Prefix, e.g.: `₿`.
+  pub prefix: Option<&'static str>,
+  /// Whether a deposit to a second "pot" address is required.
+  pub pot: bool,
+  /// Base chain of wrapped tokens.
+  pub base_chain: Option<A>,
+  /// Default minimum amount / stake.
+  pub min_stake: Balance,
+  /// Default maximum amount / stake.
+  pub max_stake: Balance,
+  /// Additional symbols the asset can be parsed from.
+  pub aliases: &'static [&'static str],
+  /// Lifecycle of the asset.
+  pub lifecycle: Lifecycle,
+  /// Denominations of the asset, from the base unit to the symbol, sorted by exponent.
+  pub denominations: &'static [Denomination],
+}
+
+impl<A> AssetInfo<A> {
+  /// Compare the asset symbol with `symbol`, ignoring the case. Usable in `const` contexts.
+  pub const fn is_symbol(&self, symbol: &str) -> bool {
+    let (left, right) = (self.symbol.as_bytes(), symbol.as_bytes());
+    if left.len() != right.len() {
+      return false;
+    }
+    let mut index = 0;
+    while index < left.len() {
+      if !left[index].eq_ignore_ascii_case(&right[index]) {
+        return false;
+      }
+      index += 1;
+    }
+    true
+  }
+}
+
+/// Error returned when a `CurrencyId` doesn't map to an asset.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CurrencyIdError {
+  /// No asset is declared with this `AssetId`.
+  UnknownAsset(AssetId),
+  /// The native asset is only represented by `CurrencyId::Tdfy` and can't be wrapped.
+  WrappedNativeAsset(AssetId),
+}
+
+impl core::fmt::Display for CurrencyIdError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    match self {
+      Self::UnknownAsset(asset_id) => write!(f, "unknown asset `{}`", asset_id),
+      Self::WrappedNativeAsset(asset_id) => {
+        write!(f, "native asset `{}` can't be wrapped", asset_id)
+      }
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CurrencyIdError {}
+
+/// Error returned when a string doesn't match any asset symbol, name or alias.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ParseAssetError {
+  /// The string which failed to parse.
+  pub input: String,
+  /// Symbols of all declared assets.
+  pub valid_symbols: &'static [&'static str],
+}
+
+impl core::fmt::Display for ParseAssetError {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "invalid asset `{}`, expected one of: ", self.input)?;
+    for (index, symbol) in self.valid_symbols.iter().enumerate() {
+      if index > 0 {
+        write!(f, ", ")?;
+      }
+      write!(f, "{}", symbol)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAssetError {}
+
 #[assets]
 pub enum Asset {
   #[asset::id = 1]
   #[asset::symbol = "TDFY"]
+  #[asset::native]
   #[asset::name = "Tidefi Token"]
   #[asset::decimals = 12]
   #[asset::algo = "SR25519"]
-  #[asset::min_stake = 10_000_000_000_000]
-  #[asset::max_stake = 500_000_000_000_000_000]
+  #[asset::min_stake = "10"]
+  #[asset::max_stake = "500_000"]
+  #[asset::network(local, address = "0x610178da211fef7d417bc0e6fed39f05609ad788")]
+  #[asset::network(devnet)]
+  #[asset::network(staging)]
+  #[asset::network(testnet)]
+  #[asset::network(mainnet)]
   Tdfy,
 
   #[asset::id = 2]
@@ -50,10 +199,17 @@ pub enum Asset {
   #[asset::decimals = 8]
   #[asset::algo = "SECP256K1"]
   #[asset::unit = "satoshi"]
+  #[asset::denomination(mBTC, exponent = 5)]
   #[asset::prefix = "₿"]
+  #[asset::alias = "XBT"]
   #[asset::pot]
-  #[asset::min_stake = 100]
-  #[asset::max_stake = 500_000_000]
+  #[asset::min_stake = "0.000_001"]
+  #[asset::max_stake = "5"]
+  #[asset::network(local)]
+  #[asset::network(devnet)]
+  #[asset::network(staging)]
+  #[asset::network(testnet)]
+  #[asset::network(mainnet)]
   Bitcoin,
 
   #[asset::id = 3]
@@ -62,9 +218,41 @@ pub enum Asset {
   #[asset::decimals = 18]
   #[asset::algo = "WEB3"]
   #[asset::unit = "wei"]
+  #[asset::denomination(gwei, exponent = 9)]
+  #[asset::denomination(ether, exponent = 18)]
   #[asset::prefix = "Ξ"]
-  #[asset::min_stake = 100_000]
-  #[asset::max_stake = 20_000_000_000_000_000_000]
+  #[asset::min_stake = "0.000_000_000_000_1"]
+  #[asset::max_stake = "20_000"]
+  #[asset::network(
+    local,
+    chain_id = 1337,
+    router = "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512",
+    multisig = "0x5fc8d32690cc91d4c39d9d3abcbd16989f875707"
+  )]
+  #[asset::network(
+    devnet,
+    chain_id = 5,
+    router = "0x3b446e2eeb7a8171bd3a41452b22971e7d17aa80",
+    multisig = "0x22f3f691392c1d6c2c96b2333f08ccf0354f97b4"
+  )]
+  #[asset::network(
+    staging,
+    chain_id = 5,
+    router = "0xeef73e2294d48004c2b06a156723f5c21495eeb1",
+    multisig = "0x87f935d2e08755eafad5d8f199914d7ba8228862"
+  )]
+  #[asset::network(
+    testnet,
+    chain_id = 5,
+    router = "0xa16ccb4c3911be7c6adc115ec5e529f5ab0abf9d",
+    multisig = "0x2d6d7a1715470d0377ed038f5674f76fbfa0db75"
+  )]
+  #[asset::network(
+    mainnet,
+    chain_id = 1,
+    router = "0x8f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c69729",
+    multisig = "0xff9d5585592507eff86d76cd9134a78e69786aa3"
+  )]
   Ethereum,
 
   #[asset::id = 4]
@@ -73,8 +261,21 @@ pub enum Asset {
   #[asset::decimals = 6]
   #[asset::algo = "WEB3"]
   #[asset::base_chain = "Ethereum"]
-  #[asset::min_stake = 1_000_000]
-  #[asset::max_stake = 100_000_000_000]
+  #[asset::min_stake = "1"]
+  #[asset::max_stake = "100_000"]
+  #[asset::network(local, address = "0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0")]
+  #[asset::network(devnet, address = "0x3fcbcc5df304cebfc3804dc8e70addf60cb05a1b")]
+  #[asset::network(staging, address = "0x348484e4a9a95dbd667398fe4f4fa6d4aaae4e18")]
+  #[asset::network(
+    testnet,
+    address = "0x74f8f1ba33f7def42ad29aba793c69497e512d2d",
+    enabled = false
+  )]
+  #[asset::network(
+    mainnet,
+    address = "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+    enabled = false
+  )]
   Tether,
 
   #[asset::id = 5]
@@ -83,8 +284,13 @@ pub enum Asset {
   #[asset::decimals = 6]
   #[asset::algo = "WEB3"]
   #[asset::base_chain = "Ethereum"]
-  #[asset::min_stake = 1_000_000]
-  #[asset::max_stake = 100_000_000_000]
+  #[asset::min_stake = "1"]
+  #[asset::max_stake = "100_000"]
+  #[asset::network(local, address = "0xa513e6e4b8f2a923d98304ec87f64353c4d5c853")]
+  #[asset::network(devnet, address = "0x71819a038e02c521db8005936b3883cecfd886c0")]
+  #[asset::network(staging, address = "0x34c7391130c375fbbef15d8bc16907f001ad8cbd")]
+  #[asset::network(testnet, address = "0xbce2733e4b0eb15278ebb9f8496d3c638d1f43dd")]
+  #[asset::network(mainnet, address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")]
   USDCoin,
 
   #[asset::id = 6]
@@ -93,14 +299,191 @@ pub enum Asset {
   #[asset::decimals = 12]
   #[asset::algo = "WEB3"]
   #[asset::base_chain = "Ethereum"]
-  #[asset::min_stake = 10_000_000_000_000]
-  #[asset::max_stake = 500_000_000_000_000_000]
+  #[asset::min_stake = "10"]
+  #[asset::max_stake = "500_000"]
+  #[asset::network(local, address = "0x8a791620dd6260079bf849dc5567adc3f2fdc318")]
+  #[asset::network(devnet)]
+  #[asset::network(staging)]
+  #[asset::network(testnet, address = "0xa6bf2a2181ef0779e86e73852f344d6cc52661ca")]
+  #[asset::network(mainnet, address = "0x527c41d75bae7f992ae1d3179621a29df9255dc4")]
   AllTimeHigh,
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  // used by the schedules of the test registries
+  #[cfg(feature = "std")]
+  use crate::networks::Schedule;
+
+  mod registry {
+    use super::*;
+
+    #[assets]
+    pub enum Token {
+      #[asset::id = 7]
+      #[asset::symbol = "NAT"]
+      #[asset::name = "Native"]
+      #[asset::decimals = 12]
+      #[asset::algo = "SR25519"]
+      #[asset::min_stake = 1]
+      #[asset::max_stake = 10]
+      #[asset::native]
+      Native,
+
+      #[asset::id = 8]
+      #[asset::symbol = "WRP"]
+      #[asset::name = "Wrapped"]
+      #[asset::decimals = 6]
+      #[asset::algo = "WEB3"]
+      #[asset::base_chain = "Native"]
+      #[asset::min_stake = 1]
+      #[asset::max_stake = 10]
+      #[asset::network(testnet, enabled_from = timestamp(1_700_000_000_000))]
+      #[asset::network(mainnet, enabled_from = block(100), disabled_at = block(200))]
+      Wrapped,
+
+      #[asset::id = 9]
+      #[asset::symbol = "OLD"]
+      #[asset::name = "Old"]
+      #[asset::decimals = 6]
+      #[asset::algo = "WEB3"]
+      #[asset::min_stake = 1]
+      #[asset::max_stake = 10]
+      #[asset::deprecated(since = "0.2.0")]
+      Old,
+
+      #[asset::id = 10]
+      #[asset::symbol = "GONE"]
+      #[asset::name = "Gone"]
+      #[asset::decimals = 6]
+      #[asset::algo = "WEB3"]
+      #[asset::min_stake = 1]
+      #[asset::max_stake = 10]
+      #[asset::network(mainnet)]
+      #[asset::status = "delisted"]
+      Gone,
+    }
+  }
+
+  mod manifest {
+    use super::*;
+
+    #[assets(file = "tests/fixtures/assets.toml")]
+    pub enum Token {}
+  }
+
+  mod json_manifest {
+    use super::*;
+
+    #[assets(file = "tests/fixtures/assets.json")]
+    pub enum Token {}
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_assets_lifecycle() {
+    use registry::Token;
+
+    assert_eq!(Asset::Bitcoin.lifecycle(), Lifecycle::Listed);
+    assert_eq!(Asset::iter_active().count(), Asset::ALL.len());
+
+    assert_eq!(
+      Token::Old.lifecycle(),
+      Lifecycle::Deprecated {
+        since: Some("0.2.0")
+      }
+    );
+    assert_eq!(Token::Gone.lifecycle(), Lifecycle::Delisted);
+    assert_eq!(
+      Token::iter_active().collect::<Vec<_>>(),
+      vec![Token::Native, Token::Wrapped, Token::Old]
+    );
+    // delisted assets are still decoded
+    assert_eq!(Token::try_from(CurrencyId::Wrapped(10)), Ok(Token::Gone));
+  }
+
+  #[test]
+  fn test_custom_registry() {
+    use registry::Token;
+
+    assert_eq!(Token::Native.currency_id(), CurrencyId::Tdfy);
+    assert_eq!(Token::Wrapped.currency_id(), CurrencyId::Wrapped(8));
+    assert_eq!(Token::try_from(CurrencyId::Tdfy), Ok(Token::Native));
+    assert_eq!(Token::try_from(CurrencyId::Wrapped(8)), Ok(Token::Wrapped));
+    assert_eq!(
+      Token::try_from(CurrencyId::Wrapped(7)),
+      Err(CurrencyIdError::WrappedNativeAsset(7))
+    );
+    assert_eq!(Token::Wrapped.base_chain(), Some(Token::Native));
+    assert_eq!("wrp".parse::<Token>(), Ok(Token::Wrapped));
+  }
+
+  #[test]
+  fn test_manifest_registry() {
+    assert_eq!(manifest::Token::ALL.len(), registry::Token::ALL.len());
+    for (loaded, declared) in manifest::Token::ALL.iter().zip(registry::Token::ALL) {
+      assert_eq!(loaded.id, declared.id);
+      assert_eq!(loaded.symbol, declared.symbol);
+      assert_eq!(loaded.name, declared.name);
+      assert_eq!(loaded.decimals, declared.decimals);
+      assert_eq!(loaded.algo, declared.algo);
+      assert_eq!(
+        loaded.base_chain.as_ref().map(|asset| asset.id()),
+        declared.base_chain.as_ref().map(|asset| asset.id())
+      );
+      assert_eq!(loaded.min_stake, declared.min_stake);
+      assert_eq!(loaded.max_stake, declared.max_stake);
+      assert_eq!(loaded.lifecycle, declared.lifecycle);
+    }
+    #[cfg(feature = "std")]
+    assert_eq!(
+      manifest::Token::Wrapped.schedule(),
+      registry::Token::Wrapped.schedule()
+    );
+    assert_eq!(manifest::Token::Native.currency_id(), CurrencyId::Tdfy);
+    assert_eq!(
+      "nat".parse::<manifest::Token>(),
+      Ok(manifest::Token::Native)
+    );
+  }
+
+  #[test]
+  fn test_json_manifest_registry() {
+    assert_eq!(json_manifest::Token::ALL.len(), manifest::Token::ALL.len());
+    for (json, toml) in json_manifest::Token::ALL.iter().zip(manifest::Token::ALL) {
+      assert_eq!(json.id, toml.id);
+      assert_eq!(json.symbol, toml.symbol);
+      assert_eq!(json.name, toml.name);
+      assert_eq!(json.decimals, toml.decimals);
+      assert_eq!(json.algo, toml.algo);
+      assert_eq!(json.min_stake, toml.min_stake);
+      assert_eq!(json.max_stake, toml.max_stake);
+      assert_eq!(json.lifecycle, toml.lifecycle);
+    }
+    #[cfg(feature = "std")]
+    assert_eq!(
+      json_manifest::Token::Wrapped.schedule(),
+      manifest::Token::Wrapped.schedule()
+    );
+    assert_eq!(
+      json_manifest::Token::Wrapped.base_chain(),
+      Some(json_manifest::Token::Native)
+    );
+  }
+
+  #[test]
+  fn test_macro_algos() {
+    // exhaustive, a new `Algo` must be added here and to the `#[assets]` macro
+    let name = |algo| match algo {
+      Algo::SR25519 => "SR25519",
+      Algo::SECP256K1 => "SECP256K1",
+      Algo::WEB3 => "WEB3",
+    };
+    let algos = [Algo::SR25519, Algo::SECP256K1, Algo::WEB3].map(name);
+    assert_eq!(algos, *tidefi_primitives_macro::__algos!());
+  }
+
   #[test]
   fn test_saturation_of_assets() {
     assert_eq!(Asset::Bitcoin.saturating_mul(10), 1_000_000_000);
@@ -110,4 +493,208 @@ mod tests {
       838_912_012_000_000
     );
   }
+
+  #[test]
+  fn test_assets_info() {
+    const BTC_DECIMALS: u8 = Asset::Bitcoin.info().decimals;
+    assert_eq!(BTC_DECIMALS, 8);
+    assert_eq!(Asset::ALL.len(), 6);
+    assert_eq!(Asset::ALL[0].asset, Asset::Tdfy);
+    for info in Asset::ALL {
+      assert_eq!(info.asset.info(), info);
+      assert_eq!(info.asset.id(), info.id);
+      assert_eq!(info.asset.symbol(), info.symbol);
+      assert_eq!(info.asset.exponent(), info.decimals);
+    }
+
+    let usdt = Asset::info_by_id(4).expect("USDT is declared");
+    assert_eq!(usdt.asset, Asset::Tether);
+    assert_eq!(usdt.base_chain, Some(Asset::Ethereum));
+    assert_eq!(Asset::info_by_id(42), None);
+
+    let btc = Asset::info_by_symbol("btc").expect("BTC is declared");
+    assert_eq!(btc.asset, Asset::Bitcoin);
+    assert_eq!(btc.unit_name, Some("satoshi"));
+    assert_eq!(btc.prefix, Some("₿"));
+    assert_eq!(btc.aliases, &["XBT"]);
+    assert_eq!(btc.algo, Algo::SECP256K1);
+    // stake limits declared in BTC, stored in satoshis
+    assert_eq!(btc.min_stake, 100);
+    assert_eq!(btc.max_stake, 500_000_000);
+    assert_eq!(Asset::info_by_symbol("Bitcoin"), None);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  #[allow(deprecated)]
+  fn test_assets_networks() {
+    use strum::IntoEnumIterator;
+    let chain_ids = Asset::Ethereum.chain_id().expect("Ethereum has chain ids");
+    assert_eq!(chain_ids.get(&Network::Local), Some(&1337));
+    assert_eq!(chain_ids.get(&Network::Mainnet), Some(&1));
+    assert_eq!(Asset::Bitcoin.chain_id(), None);
+    assert!(Asset::Ethereum.router().is_some());
+    assert!(Asset::Ethereum.multisig().is_some());
+    assert_eq!(Asset::Tether.router(), None);
+
+    let addresses = Asset::AllTimeHigh.address().expect("ATH is deployed");
+    assert_eq!(
+      addresses.keys().collect::<Vec<_>>(),
+      vec![&Network::Local, &Network::Testnet, &Network::Mainnet]
+    );
+    assert_eq!(
+      Asset::Tdfy
+        .address()
+        .expect("TDFY is deployed locally")
+        .len(),
+      1
+    );
+
+    let enabled = Asset::Tether.enabled();
+    assert_eq!(enabled.len(), 5);
+    assert_eq!(enabled.get(&Network::Staging), Some(&true));
+    assert_eq!(enabled.get(&Network::Mainnet), Some(&false));
+
+    assert!(Asset::iter().all(|asset| asset.schedule().is_empty()));
+    assert_eq!(
+      Asset::Tdfy.is_enabled_at(&Network::Mainnet, SchedulePoint::Block(0)),
+      Some(true)
+    );
+    assert_eq!(
+      Asset::Tether.is_enabled_at(&Network::Mainnet, SchedulePoint::Block(0)),
+      Some(false)
+    );
+
+    use registry::Token;
+    assert_eq!(
+      Token::Wrapped.schedule()[&Network::Mainnet],
+      Schedule {
+        enabled_from: Some(SchedulePoint::Block(100)),
+        disabled_at: Some(SchedulePoint::Block(200)),
+      }
+    );
+    let mainnet = |at| Token::Wrapped.is_enabled_at(&Network::Mainnet, at);
+    assert_eq!(mainnet(SchedulePoint::Block(99)), Some(false));
+    assert_eq!(mainnet(SchedulePoint::Block(100)), Some(true));
+    assert_eq!(mainnet(SchedulePoint::Block(200)), Some(false));
+    // listed at a block, unknown at a timestamp
+    assert_eq!(mainnet(SchedulePoint::Timestamp(1_700_000_000_000)), None);
+    assert_eq!(
+      Token::Wrapped.is_enabled_at(
+        &Network::Testnet,
+        SchedulePoint::Timestamp(1_700_000_000_000)
+      ),
+      Some(true)
+    );
+    // not declared
+    assert_eq!(
+      Token::Wrapped.is_enabled_at(&Network::Devnet, SchedulePoint::Block(100)),
+      Some(false)
+    );
+    // still declared on mainnet, but delisted
+    assert!(Token::Gone.enabled_on(&Network::Mainnet));
+    assert_eq!(
+      Token::Gone.is_enabled_at(&Network::Mainnet, SchedulePoint::Block(0)),
+      Some(false)
+    );
+
+    assert_eq!(Asset::Ethereum.chain_id_on(&Network::Testnet), Some(5));
+    assert_eq!(Asset::Bitcoin.chain_id_on(&Network::Testnet), None);
+    assert_eq!(
+      Asset::Ethereum.router_on(&Network::Mainnet),
+      "0x8f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c69729".parse().ok()
+    );
+    assert!(Asset::Ethereum.multisig_on(&Network::Local).is_some());
+    assert_eq!(Asset::AllTimeHigh.address_on(&Network::Devnet), None);
+    assert!(Asset::USDCoin.enabled_on(&Network::Mainnet));
+    assert!(!Asset::Tether.enabled_on(&Network::Mainnet));
+
+    // serialized with the lowercase network names
+    let json = serde_json::to_string(&Asset::Ethereum.chain_id()).unwrap();
+    assert_eq!(
+      json,
+      r#"{"local":1337,"devnet":5,"staging":5,"testnet":5,"mainnet":1}"#
+    );
+    assert_eq!(
+      serde_json::from_str::<ChainIds>(&json).ok(),
+      Asset::Ethereum.chain_id()
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn test_builtin_addresses() {
+    use strum::IntoEnumIterator;
+    // checksums are verified by the macro, the addresses are displayed checksummed
+    for asset in Asset::iter() {
+      let addresses = [asset.router(), asset.multisig(), asset.address()];
+      for address in addresses
+        .into_iter()
+        .flatten()
+        .flat_map(|map| map.into_values())
+      {
+        assert_eq!(address.to_string().parse::<EvmAddress>(), Ok(address));
+      }
+    }
+    assert_eq!(
+      Asset::Tether.address().unwrap()[&Network::Mainnet].to_string(),
+      "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+    );
+    assert_eq!(
+      Asset::Tether.address_on(&Network::Devnet).unwrap(),
+      "0x3fcbcc5df304cebfc3804dc8e70addf60cb05a1b"
+        .parse()
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn test_currency_id_bijection() {
+    assert_eq!(Asset::try_from(CurrencyId::Tdfy), Ok(Asset::Tdfy));
+    assert_eq!(
+      Asset::try_from(CurrencyId::Wrapped(1)),
+      Err(CurrencyIdError::WrappedNativeAsset(1))
+    );
+    assert_eq!(Asset::try_from(CurrencyId::Wrapped(2)), Ok(Asset::Bitcoin));
+    assert_eq!(
+      Asset::try_from(CurrencyId::Wrapped(42)),
+      Err(CurrencyIdError::UnknownAsset(42))
+    );
+  }
+
+  #[test]
+  fn test_assets_codec() {
+    use codec::{Decode, Encode, MaxEncodedLen};
+    use scale_info::TypeInfo;
+
+    assert_eq!(Asset::Bitcoin.encode(), 2_u32.encode());
+    assert_eq!(Asset::Tdfy.encode(), 1_u32.encode());
+    for info in Asset::ALL {
+      let encoded = info.asset.encode();
+      assert_eq!(encoded.len(), Asset::max_encoded_len());
+      assert_eq!(Asset::decode(&mut &encoded[..]), Ok(info.asset.clone()));
+    }
+    assert!(Asset::decode(&mut &42_u32.encode()[..]).is_err());
+    assert!(Asset::decode(&mut &[2_u8][..]).is_err());
+
+    let type_info = Asset::type_info();
+    assert_eq!(type_info.path.ident(), Some("Asset"));
+  }
+
+  #[test]
+  fn test_parse_assets() {
+    assert_eq!("BTC".parse::<Asset>(), Ok(Asset::Bitcoin));
+    assert_eq!("btc".parse::<Asset>(), Ok(Asset::Bitcoin));
+    assert_eq!("Bitcoin".parse::<Asset>(), Ok(Asset::Bitcoin));
+    assert_eq!("xbt".parse::<Asset>(), Ok(Asset::Bitcoin));
+    assert_eq!("usd coin".parse::<Asset>(), Ok(Asset::USDCoin));
+    assert_eq!(Asset::try_from("Tdfy"), Ok(Asset::Tdfy));
+
+    let error = "DOGE".parse::<Asset>().unwrap_err();
+    assert_eq!(error.input, "DOGE");
+    assert_eq!(
+      error.to_string(),
+      "invalid asset `DOGE`, expected one of: TDFY, BTC, ETH, USDT, USDC, ATH"
+    );
+  }
 }
@@ -0,0 +1,950 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum and EVM compatible chains: addresses, ABI encoded calls to the router and the
+//! ERC-20 tokens, and unsigned EIP-1559 transactions.
+
+use codec::{alloc::vec::Vec, Decode, Encode, MaxEncodedLen};
+use core::{fmt, str::FromStr};
+use scale_info::TypeInfo;
+use sha3::{Digest, Keccak256};
+use sp_core::U256;
+
+#[cfg(feature = "std")]
+use crate::{
+  assets::{Algo, Asset},
+  networks::{Network, NetworkRegistry},
+  AccountId, Balance,
+};
+
+/// Address of an EVM account or contract, displayed with its EIP-55 checksum, e.g.:
+/// `0xdAC17F958D2ee523a2206206994597C13D831ec7`.
+#[derive(
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Encode,
+  Decode,
+  TypeInfo,
+  MaxEncodedLen,
+)]
+pub struct EvmAddress(pub [u8; 20]);
+
+impl EvmAddress {
+  pub const fn new(bytes: [u8; 20]) -> Self {
+    Self(bytes)
+  }
+
+  pub const fn as_bytes(&self) -> &[u8; 20] {
+    &self.0
+  }
+
+  /// Lowercase hex digits of the address, without the `0x` prefix.
+  fn hex(&self) -> [u8; 40] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = [0; 40];
+    for (index, byte) in self.0.iter().enumerate() {
+      hex[index * 2] = DIGITS[usize::from(byte >> 4)];
+      hex[index * 2 + 1] = DIGITS[usize::from(byte & 0xf)];
+    }
+    hex
+  }
+
+  /// EIP-55 checksummed hex digits of the address, without the `0x` prefix: a letter is
+  /// uppercase when the matching nibble of the keccak hash of the lowercase hex is `>= 8`.
+  fn checksummed_hex(&self) -> [u8; 40] {
+    let mut hex = self.hex();
+    let hash = keccak_256(&hex);
+    for (index, digit) in hex.iter_mut().enumerate() {
+      let nibble = if index % 2 == 0 {
+        hash[index / 2] >> 4
+      } else {
+        hash[index / 2] & 0xf
+      };
+      if nibble >= 8 {
+        digit.make_ascii_uppercase();
+      }
+    }
+    hex
+  }
+}
+
+impl From<[u8; 20]> for EvmAddress {
+  fn from(bytes: [u8; 20]) -> Self {
+    Self(bytes)
+  }
+}
+
+impl fmt::Display for EvmAddress {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("0x")?;
+    for digit in self.checksummed_hex() {
+      write!(f, "{}", char::from(digit))?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Debug for EvmAddress {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+/// Error returned when a string isn't a valid EVM address.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EvmAddressError {
+  /// The string isn't `0x` followed by 40 characters.
+  InvalidLength,
+  /// The string contains a character which isn't a hex digit.
+  InvalidCharacter,
+  /// The string mixes cases and doesn't match the EIP-55 checksum of the address.
+  InvalidChecksum,
+}
+
+impl fmt::Display for EvmAddressError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::InvalidLength => write!(f, "expected `0x` followed by 40 hex digits"),
+      Self::InvalidCharacter => write!(f, "invalid hex digit"),
+      Self::InvalidChecksum => write!(f, "invalid EIP-55 checksum"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EvmAddressError {}
+
+impl FromStr for EvmAddress {
+  type Err = EvmAddressError;
+
+  /// Parse a `0x` prefixed address, its checksum is only verified when it mixes cases as
+  /// lowercase or uppercase addresses don't carry one.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let hex = s
+      .strip_prefix("0x")
+      .filter(|hex| hex.len() == 40)
+      .ok_or(EvmAddressError::InvalidLength)?
+      .as_bytes();
+
+    let mut bytes = [0; 20];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+      let nibble = |digit: u8| {
+        char::from(digit)
+          .to_digit(16)
+          .map(|nibble| nibble as u8)
+          .ok_or(EvmAddressError::InvalidCharacter)
+      };
+      *byte = nibble(pair[0])? << 4 | nibble(pair[1])?;
+    }
+    let address = Self(bytes);
+
+    let has_lowercase = hex.iter().any(u8::is_ascii_lowercase);
+    let has_uppercase = hex.iter().any(u8::is_ascii_uppercase);
+    if has_lowercase && has_uppercase && address.checksummed_hex()[..] != hex[..] {
+      return Err(EvmAddressError::InvalidChecksum);
+    }
+    Ok(address)
+  }
+}
+
+#[cfg(feature = "std")]
+impl serde::Serialize for EvmAddress {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for EvmAddress {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let address = String::deserialize(deserializer)?;
+    address.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// Router `deposit(address token, uint256 amount, bytes32 account)`, `token` is the zero
+/// address when depositing ETH sent as the value of the transaction.
+pub const ROUTER_DEPOSIT: &str = "deposit(address,uint256,bytes32)";
+/// Router `withdraw(address token, address recipient, uint256 amount)`, `token` is the zero
+/// address when withdrawing ETH.
+pub const ROUTER_WITHDRAW: &str = "withdraw(address,address,uint256)";
+/// ERC-20 `approve(address spender, uint256 amount)`.
+pub const ERC20_APPROVE: &str = "approve(address,uint256)";
+/// ERC-20 `transfer(address recipient, uint256 amount)`.
+pub const ERC20_TRANSFER: &str = "transfer(address,uint256)";
+
+/// A static ABI argument, encoded in a 32 bytes word.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Token {
+  Address(EvmAddress),
+  Uint(U256),
+  FixedBytes([u8; 32]),
+}
+
+/// Keccak-256 hash used by Ethereum, `sp_core::hashing` only has it with `full_crypto`.
+fn keccak_256(data: &[u8]) -> [u8; 32] {
+  Keccak256::digest(data).into()
+}
+
+/// Return the selector of a function, e.g.: `0xa9059cbb` for `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+  let hash = keccak_256(signature.as_bytes());
+  [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// ABI encode a call to a function taking static arguments.
+pub fn encode_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+  let mut data = Vec::with_capacity(4 + 32 * tokens.len());
+  data.extend_from_slice(&selector(signature));
+  for token in tokens {
+    let mut word = [0; 32];
+    match token {
+      Token::Address(address) => word[12..].copy_from_slice(address.as_bytes()),
+      Token::Uint(value) => value.to_big_endian(&mut word),
+      Token::FixedBytes(bytes) => word = *bytes,
+    }
+    data.extend_from_slice(&word);
+  }
+  data
+}
+
+/// A contract call, ready to be sent in a transaction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EvmCall {
+  pub chain_id: u32,
+  pub to: EvmAddress,
+  pub value: U256,
+  pub data: Vec<u8>,
+}
+
+impl EvmCall {
+  /// Wrap the call in an unsigned EIP-1559 transaction.
+  pub fn into_eip1559(
+    self,
+    nonce: u64,
+    max_priority_fee_per_gas: U256,
+    max_fee_per_gas: U256,
+    gas_limit: u64,
+  ) -> Eip1559Transaction {
+    Eip1559Transaction {
+      chain_id: self.chain_id.into(),
+      nonce,
+      max_priority_fee_per_gas,
+      max_fee_per_gas,
+      gas_limit,
+      to: self.to,
+      value: self.value,
+      data: self.data,
+    }
+  }
+}
+
+/// Unsigned EIP-1559 transaction, without access list.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Eip1559Transaction {
+  pub chain_id: u64,
+  pub nonce: u64,
+  pub max_priority_fee_per_gas: U256,
+  pub max_fee_per_gas: U256,
+  pub gas_limit: u64,
+  pub to: EvmAddress,
+  pub value: U256,
+  pub data: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+  /// Transaction type of EIP-2718.
+  pub const TYPE: u8 = 0x02;
+
+  /// Return the unsigned payload, `0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+  /// max_fee_per_gas, gas_limit, to, value, data, access_list])`.
+  pub fn encode(&self) -> Vec<u8> {
+    let mut fields = Vec::new();
+    rlp::encode_uint(&mut fields, self.chain_id.into());
+    rlp::encode_uint(&mut fields, self.nonce.into());
+    rlp::encode_uint(&mut fields, self.max_priority_fee_per_gas);
+    rlp::encode_uint(&mut fields, self.max_fee_per_gas);
+    rlp::encode_uint(&mut fields, self.gas_limit.into());
+    rlp::encode_bytes(&mut fields, self.to.as_bytes());
+    rlp::encode_uint(&mut fields, self.value);
+    rlp::encode_bytes(&mut fields, &self.data);
+    // empty access list
+    rlp::encode_list(&mut fields, &[]);
+
+    let mut payload = Vec::with_capacity(fields.len() + 4);
+    payload.push(Self::TYPE);
+    rlp::encode_list(&mut payload, &fields);
+    payload
+  }
+
+  /// Return the hash to sign, the keccak hash of the unsigned payload.
+  pub fn signing_hash(&self) -> [u8; 32] {
+    keccak_256(&self.encode())
+  }
+}
+
+/// Recursive length prefix encoding.
+mod rlp {
+  use super::{Vec, U256};
+
+  fn encode_header(out: &mut Vec<u8>, offset: u8, len: usize) {
+    if len < 56 {
+      out.push(offset + len as u8);
+    } else {
+      let len = (len as u64).to_be_bytes();
+      let len = &len[len.iter().take_while(|byte| **byte == 0).count()..];
+      out.push(offset + 55 + len.len() as u8);
+      out.extend_from_slice(len);
+    }
+  }
+
+  pub fn encode_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    if let [byte] = bytes {
+      if *byte < 0x80 {
+        out.push(*byte);
+        return;
+      }
+    }
+    encode_header(out, 0x80, bytes.len());
+    out.extend_from_slice(bytes);
+  }
+
+  /// Integers are big endian without leading zeros, zero being the empty string.
+  pub fn encode_uint(out: &mut Vec<u8>, value: U256) {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    encode_bytes(out, &bytes[leading_zeros..]);
+  }
+
+  /// Encode a list from its encoded items.
+  pub fn encode_list(out: &mut Vec<u8>, items: &[u8]) {
+    encode_header(out, 0xc0, items.len());
+    out.extend_from_slice(items);
+  }
+}
+
+/// Error returned when a call can't be built for an asset on a network.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EvmCallError {
+  /// The asset doesn't live on an EVM chain.
+  UnsupportedAsset,
+  /// The asset isn't an ERC-20 token.
+  NotAToken,
+  /// The asset has no chain id on the network.
+  MissingChainId,
+  /// The asset has no router on the network.
+  MissingRouter,
+  /// The token has no contract address on the network.
+  MissingAddress,
+}
+
+impl fmt::Display for EvmCallError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::UnsupportedAsset => write!(f, "asset doesn't live on an EVM chain"),
+      Self::NotAToken => write!(f, "asset isn't an ERC-20 token"),
+      Self::MissingChainId => write!(f, "no chain id on the network"),
+      Self::MissingRouter => write!(f, "no router on the network"),
+      Self::MissingAddress => write!(f, "no token address on the network"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EvmCallError {}
+
+#[cfg(feature = "std")]
+impl Asset {
+  /// Return the EVM chain the asset lives on, itself or its base chain.
+  fn evm_chain(&self) -> Result<Asset, EvmCallError> {
+    let chain = self.base_chain().unwrap_or_else(|| self.clone());
+    match chain.algo() {
+      Algo::WEB3 => Ok(chain),
+      Algo::SR25519 | Algo::SECP256K1 => Err(EvmCallError::UnsupportedAsset),
+    }
+  }
+
+  /// Return a call to the contract of the token, the chain id of its chain and its address
+  /// on the network are looked up by `chain_id` and `address`.
+  fn erc20_call(
+    &self,
+    chain_id: impl Fn(&Asset) -> Option<u32>,
+    address: impl Fn(&Asset) -> Option<EvmAddress>,
+    signature: &str,
+    tokens: &[Token],
+  ) -> Result<EvmCall, EvmCallError> {
+    let chain = self.evm_chain()?;
+    if chain == *self {
+      return Err(EvmCallError::NotAToken);
+    }
+    let to = address(self).ok_or(EvmCallError::MissingAddress)?;
+    let chain_id = chain_id(&chain).ok_or(EvmCallError::MissingChainId)?;
+    Ok(EvmCall {
+      chain_id,
+      to,
+      value: 0.into(),
+      data: encode_call(signature, tokens),
+    })
+  }
+
+  /// Return a call to the router of the chain of the asset, its arguments are built from the
+  /// token address, the zero address for the coin of the chain. The chain id and the router
+  /// of the chain, and the address of the token, are looked up by `chain_id`, `router` and
+  /// `address`.
+  fn router_call(
+    &self,
+    chain_id: impl Fn(&Asset) -> Option<u32>,
+    router: impl Fn(&Asset) -> Option<EvmAddress>,
+    address: impl Fn(&Asset) -> Option<EvmAddress>,
+    call: impl FnOnce(EvmAddress) -> (Balance, &'static str, Vec<Token>),
+  ) -> Result<EvmCall, EvmCallError> {
+    let chain = self.evm_chain()?;
+    let to = router(&chain).ok_or(EvmCallError::MissingRouter)?;
+    let token = if chain == *self {
+      EvmAddress::default()
+    } else {
+      address(self).ok_or(EvmCallError::MissingAddress)?
+    };
+    let chain_id = chain_id(&chain).ok_or(EvmCallError::MissingChainId)?;
+    let (value, signature, tokens) = call(token);
+    Ok(EvmCall {
+      chain_id,
+      to,
+      value: value.into(),
+      data: encode_call(signature, &tokens),
+    })
+  }
+
+  /// Return the router `deposit` of the asset, credited to the Tidechain account. Tokens must
+  /// have been approved to the router first, ETH is sent as the value of the call.
+  /// Only the built-in deployments are used, see `NetworkRegistry::router_deposit`.
+  pub fn router_deposit(
+    &self,
+    network: &Network,
+    account: &AccountId,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    self.router_call(
+      |chain| chain.chain_id_on(network),
+      |chain| chain.router_on(network),
+      |token| token.address_on(network),
+      |token| deposit_args(token, account, amount),
+    )
+  }
+
+  /// Return the router `withdraw` of the asset to the recipient.
+  /// Only the built-in deployments are used, see `NetworkRegistry::router_withdraw`.
+  pub fn router_withdraw(
+    &self,
+    network: &Network,
+    recipient: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    self.router_call(
+      |chain| chain.chain_id_on(network),
+      |chain| chain.router_on(network),
+      |token| token.address_on(network),
+      |token| withdraw_args(token, recipient, amount),
+    )
+  }
+
+  /// Return the ERC-20 `approve` of the token, e.g. to let the router pull a deposit.
+  /// Only the built-in deployments are used, see `NetworkRegistry::erc20_approve`.
+  pub fn erc20_approve(
+    &self,
+    network: &Network,
+    spender: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    self.erc20_call(
+      |chain| chain.chain_id_on(network),
+      |token| token.address_on(network),
+      ERC20_APPROVE,
+      &[Token::Address(spender), Token::Uint(amount.into())],
+    )
+  }
+
+  /// Return the ERC-20 `transfer` of the token.
+  /// Only the built-in deployments are used, see `NetworkRegistry::erc20_transfer`.
+  pub fn erc20_transfer(
+    &self,
+    network: &Network,
+    recipient: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    self.erc20_call(
+      |chain| chain.chain_id_on(network),
+      |token| token.address_on(network),
+      ERC20_TRANSFER,
+      &[Token::Address(recipient), Token::Uint(amount.into())],
+    )
+  }
+}
+
+/// Value and arguments of a router `deposit`, the amount is sent as value for the coin of the
+/// chain, the zero address token.
+#[cfg(feature = "std")]
+fn deposit_args(
+  token: EvmAddress,
+  account: &AccountId,
+  amount: Balance,
+) -> (Balance, &'static str, Vec<Token>) {
+  let value = if token == EvmAddress::default() {
+    amount
+  } else {
+    0
+  };
+  let tokens = vec![
+    Token::Address(token),
+    Token::Uint(amount.into()),
+    Token::FixedBytes(account.clone().into()),
+  ];
+  (value, ROUTER_DEPOSIT, tokens)
+}
+
+/// Value and arguments of a router `withdraw`.
+#[cfg(feature = "std")]
+fn withdraw_args(
+  token: EvmAddress,
+  recipient: EvmAddress,
+  amount: Balance,
+) -> (Balance, &'static str, Vec<Token>) {
+  let tokens = vec![
+    Token::Address(token),
+    Token::Address(recipient),
+    Token::Uint(amount.into()),
+  ];
+  (0, ROUTER_WITHDRAW, tokens)
+}
+
+#[cfg(feature = "std")]
+impl NetworkRegistry {
+  /// Return the router `deposit` of the asset with the deployments of the registry, which
+  /// include the overlaid and custom networks.
+  pub fn router_deposit(
+    &self,
+    asset: &Asset,
+    network: &Network,
+    account: &AccountId,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    asset.router_call(
+      |chain| self.chain_id(chain, network),
+      |chain| self.router(chain, network),
+      |token| self.address(token, network),
+      |token| deposit_args(token, account, amount),
+    )
+  }
+
+  /// Return the router `withdraw` of the asset with the deployments of the registry.
+  pub fn router_withdraw(
+    &self,
+    asset: &Asset,
+    network: &Network,
+    recipient: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    asset.router_call(
+      |chain| self.chain_id(chain, network),
+      |chain| self.router(chain, network),
+      |token| self.address(token, network),
+      |token| withdraw_args(token, recipient, amount),
+    )
+  }
+
+  /// Return the ERC-20 `approve` of the token with the deployments of the registry, which
+  /// include the overlaid and custom networks.
+  pub fn erc20_approve(
+    &self,
+    asset: &Asset,
+    network: &Network,
+    spender: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    asset.erc20_call(
+      |chain| self.chain_id(chain, network),
+      |token| self.address(token, network),
+      ERC20_APPROVE,
+      &[Token::Address(spender), Token::Uint(amount.into())],
+    )
+  }
+
+  /// Return the ERC-20 `transfer` of the token with the deployments of the registry.
+  pub fn erc20_transfer(
+    &self,
+    asset: &Asset,
+    network: &Network,
+    recipient: EvmAddress,
+    amount: Balance,
+  ) -> Result<EvmCall, EvmCallError> {
+    asset.erc20_call(
+      |chain| self.chain_id(chain, network),
+      |token| self.address(token, network),
+      ERC20_TRANSFER,
+      &[Token::Address(recipient), Token::Uint(amount.into())],
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_evm_address() {
+    // test vectors of EIP-55
+    for checksummed in [
+      "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+      "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+      "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+      "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ] {
+      let address = EvmAddress::from_str(checksummed).unwrap();
+      assert_eq!(address.to_string(), checksummed);
+      assert_eq!(
+        EvmAddress::from_str(&checksummed.to_lowercase()),
+        Ok(address)
+      );
+      assert_eq!(
+        EvmAddress::from_str(&format!("0x{}", checksummed[2..].to_uppercase())),
+        Ok(address)
+      );
+    }
+
+    assert_eq!(
+      EvmAddress::from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+      Err(EvmAddressError::InvalidChecksum)
+    );
+    assert_eq!(
+      EvmAddress::from_str("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+      Err(EvmAddressError::InvalidLength)
+    );
+    assert_eq!(
+      EvmAddress::from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea"),
+      Err(EvmAddressError::InvalidLength)
+    );
+    assert_eq!(
+      EvmAddress::from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beagd"),
+      Err(EvmAddressError::InvalidCharacter)
+    );
+  }
+
+  fn hex(data: &[u8]) -> String {
+    sp_core::hexdisplay::HexDisplay::from(&data).to_string()
+  }
+
+  const RECIPIENT: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+  // The transaction payloads and signing hashes below were produced independently with alloy:
+  // `TxEip1559::encode_for_signing` and `signature_hash`, on calls encoded with `sol!`.
+
+  #[test]
+  fn test_encode_call() {
+    assert_eq!(selector(ERC20_TRANSFER), [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(selector(ERC20_APPROVE), [0x09, 0x5e, 0xa7, 0xb3]);
+    assert_eq!(selector(ROUTER_DEPOSIT), [0x26, 0xb3, 0x29, 0x3f]);
+    assert_eq!(selector(ROUTER_WITHDRAW), [0xd9, 0xca, 0xed, 0x12]);
+
+    let recipient = EvmAddress::from_str(RECIPIENT).unwrap();
+    assert_eq!(
+      hex(&encode_call(
+        ERC20_TRANSFER,
+        &[Token::Address(recipient), Token::Uint(1_000_000.into())]
+      )),
+      concat!(
+        "a9059cbb",
+        "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        "00000000000000000000000000000000000000000000000000000000000f4240",
+      )
+    );
+  }
+
+  #[test]
+  fn test_eip1559_transaction() {
+    let call = EvmCall {
+      chain_id: 1,
+      to: EvmAddress::from_str(RECIPIENT).unwrap(),
+      value: 0.into(),
+      data: Vec::new(),
+    };
+    let transaction = call.clone().into_eip1559(0, 1.into(), 2.into(), 21_000);
+    assert_eq!(
+      hex(&transaction.encode()),
+      "02df01800102825208945aaeb6053f3e94c9b9a09f33669435e7ef1beaed8080c0"
+    );
+    assert_eq!(
+      hex(&transaction.signing_hash()),
+      "36808b0ddf5c2cbdef1c78b60a3f55015114802efad5b4c644f844c7fc91bb78"
+    );
+
+    // 1 ETH
+    let call = EvmCall {
+      value: 1_000_000_000_000_000_000_u128.into(),
+      ..call
+    };
+    let transaction = call.into_eip1559(0, 1.into(), 2.into(), 21_000);
+    assert_eq!(
+      hex(&transaction.encode()),
+      "02e701800102825208945aaeb6053f3e94c9b9a09f33669435e7ef1beaed880de0b6b3a764000080c0"
+    );
+    assert_eq!(
+      hex(&transaction.signing_hash()),
+      "75bf50f244602dede1354cce46f0351f0fdd4d2ef641ed363a7676ba6adc63af"
+    );
+  }
+
+  #[test]
+  fn test_asset_calls() {
+    let recipient = EvmAddress::from_str(RECIPIENT).unwrap();
+    let usdt = EvmAddress::from_str("0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap();
+
+    let transfer = Asset::Tether
+      .erc20_transfer(&Network::Mainnet, recipient, 1_000_000)
+      .unwrap();
+    assert_eq!(transfer.chain_id, 1);
+    assert_eq!(transfer.to, usdt);
+    assert_eq!(transfer.value, 0.into());
+    assert_eq!(
+      hex(
+        &transfer
+          .clone()
+          .into_eip1559(9, 1.into(), 2.into(), 65_000)
+          .encode()
+      ),
+      concat!(
+        "02f8640109010282fde894dac17f958d2ee523a2206206994597c13d831ec780b844",
+        "a9059cbb",
+        "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        "00000000000000000000000000000000000000000000000000000000000f4240",
+        "c0",
+      )
+    );
+
+    assert_eq!(
+      hex(
+        &transfer
+          .into_eip1559(9, 1.into(), 2.into(), 65_000)
+          .signing_hash()
+      ),
+      "8f6657972ea653d2f6a83854a0f993d3ca8f25d12b3673fcd76c4faefe32fd22"
+    );
+
+    let router = Asset::Ethereum.router_on(&Network::Mainnet).unwrap();
+    let approve = Asset::Tether
+      .erc20_approve(&Network::Mainnet, router, 1_000_000)
+      .unwrap();
+    assert_eq!(approve.to, usdt);
+    let approve = approve.into_eip1559(9, 1.into(), 2.into(), 65_000);
+    assert_eq!(
+      hex(&approve.encode()),
+      concat!(
+        "02f8640109010282fde894dac17f958d2ee523a2206206994597c13d831ec780b844",
+        "095ea7b3",
+        "0000000000000000000000008f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c69729",
+        "00000000000000000000000000000000000000000000000000000000000f4240",
+        "c0",
+      )
+    );
+    assert_eq!(
+      hex(&approve.signing_hash()),
+      "d90c8ceaca2754867bed428e7541215382cc445620e51bb9df95a83d57556b76"
+    );
+
+    // ETH is sent as value with the zero address as token
+    let account = AccountId::from([1; 32]);
+    let deposit = Asset::Ethereum
+      .router_deposit(&Network::Testnet, &account, 42)
+      .unwrap();
+    assert_eq!(deposit.chain_id, 5);
+    assert_eq!(deposit.value, 42.into());
+    let deposit = deposit.into_eip1559(3, 1.into(), 2.into(), 100_000);
+    assert_eq!(
+      hex(&deposit.encode()),
+      concat!(
+        "02f88505030102830186a094a16ccb4c3911be7c6adc115ec5e529f5ab0abf9d2ab864",
+        "26b3293f",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "000000000000000000000000000000000000000000000000000000000000002a",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        "c0",
+      )
+    );
+    assert_eq!(
+      hex(&deposit.signing_hash()),
+      "dee8cf91ddf367429d7852613852b8e7640de34b753ed30e886d08d8b7237e0f"
+    );
+
+    let deposit = Asset::USDCoin
+      .router_deposit(&Network::Testnet, &account, 42)
+      .unwrap()
+      .into_eip1559(3, 1.into(), 2.into(), 100_000);
+    assert_eq!(
+      hex(&deposit.encode()),
+      concat!(
+        "02f88505030102830186a094a16ccb4c3911be7c6adc115ec5e529f5ab0abf9d80b864",
+        "26b3293f",
+        "000000000000000000000000bce2733e4b0eb15278ebb9f8496d3c638d1f43dd",
+        "000000000000000000000000000000000000000000000000000000000000002a",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        "c0",
+      )
+    );
+    assert_eq!(
+      hex(&deposit.signing_hash()),
+      "62aa6a0d2b68d350c5e88ce70ded09f78437791f4e737dce08fc8057c1cf6060"
+    );
+
+    let withdraw = Asset::AllTimeHigh
+      .router_withdraw(&Network::Mainnet, recipient, 7)
+      .unwrap();
+    assert_eq!(withdraw.to, router);
+    let withdraw = withdraw.into_eip1559(3, 1.into(), 2.into(), 100_000);
+    assert_eq!(
+      hex(&withdraw.encode()),
+      concat!(
+        "02f88501030102830186a0948f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c6972980b864",
+        "d9caed12",
+        "000000000000000000000000527c41d75bae7f992ae1d3179621a29df9255dc4",
+        "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        "0000000000000000000000000000000000000000000000000000000000000007",
+        "c0",
+      )
+    );
+    assert_eq!(
+      hex(&withdraw.signing_hash()),
+      "344d5e3fae65bd648228e312aa5ff2c2d108e3f829e07484d546570d3a5f9af1"
+    );
+
+    assert_eq!(
+      Asset::Bitcoin.router_withdraw(&Network::Mainnet, recipient, 7),
+      Err(EvmCallError::UnsupportedAsset)
+    );
+    assert_eq!(
+      Asset::Tether.router_deposit(&Network::Custom("ci".to_string()), &account, 7),
+      Err(EvmCallError::MissingRouter)
+    );
+    assert_eq!(
+      Asset::AllTimeHigh.router_withdraw(&Network::Devnet, recipient, 7),
+      Err(EvmCallError::MissingAddress)
+    );
+    assert_eq!(
+      Asset::Bitcoin.erc20_transfer(&Network::Mainnet, recipient, 7),
+      Err(EvmCallError::UnsupportedAsset)
+    );
+    assert_eq!(
+      Asset::Ethereum.erc20_transfer(&Network::Mainnet, recipient, 7),
+      Err(EvmCallError::NotAToken)
+    );
+    assert_eq!(
+      Asset::AllTimeHigh.erc20_transfer(&Network::Devnet, recipient, 7),
+      Err(EvmCallError::MissingAddress)
+    );
+    assert_eq!(
+      Asset::Tether.erc20_transfer(&Network::Custom("ci".to_string()), recipient, 7),
+      Err(EvmCallError::MissingAddress)
+    );
+  }
+
+  #[test]
+  fn test_registry_calls() {
+    let recipient = EvmAddress::from_str(RECIPIENT).unwrap();
+    let ci = Network::Custom("ci".to_string());
+    let token = EvmAddress::from_str("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359").unwrap();
+
+    let mut registry = NetworkRegistry::builtin();
+    assert_eq!(
+      registry.erc20_transfer(&Asset::Tether, &Network::Mainnet, recipient, 7),
+      Asset::Tether.erc20_transfer(&Network::Mainnet, recipient, 7)
+    );
+    assert_eq!(
+      registry.erc20_transfer(&Asset::Tether, &ci, recipient, 7),
+      Err(EvmCallError::MissingAddress)
+    );
+
+    registry
+      .overlay_json(&format!(
+        r#"{{ "ETH": {{ "custom:ci": {{ "chainId": 31337 }} }}, "USDT": {{ "custom:ci": {{ "address": "{}" }} }}, "ATH": {{ "devnet": {{ "address": "{}" }} }} }}"#,
+        token, token
+      ))
+      .unwrap();
+    let transfer = registry
+      .erc20_transfer(&Asset::Tether, &ci, recipient, 7)
+      .unwrap();
+    assert_eq!(transfer.chain_id, 31337);
+    assert_eq!(transfer.to, token);
+    assert_eq!(transfer.data[..4], selector(ERC20_TRANSFER));
+
+    let approve = registry
+      .erc20_approve(&Asset::AllTimeHigh, &Network::Devnet, recipient, 7)
+      .unwrap();
+    assert_eq!(
+      approve.chain_id,
+      Asset::Ethereum.chain_id_on(&Network::Devnet).unwrap()
+    );
+    assert_eq!(approve.to, token);
+    assert_eq!(
+      Asset::AllTimeHigh.erc20_approve(&Network::Devnet, recipient, 7),
+      Err(EvmCallError::MissingAddress)
+    );
+
+    let account = AccountId::from([1; 32]);
+    assert_eq!(
+      registry.router_deposit(&Asset::Tether, &ci, &account, 7),
+      Err(EvmCallError::MissingRouter)
+    );
+    registry
+      .overlay_vars([(
+        "TIDEFI_CUSTOM_CI_ETH_ROUTER".to_string(),
+        RECIPIENT.to_string(),
+      )])
+      .unwrap();
+    let deposit = registry
+      .router_deposit(&Asset::Tether, &ci, &account, 7)
+      .unwrap();
+    assert_eq!(deposit.chain_id, 31337);
+    assert_eq!(deposit.to, recipient);
+    assert_eq!(deposit.value, 0.into());
+    assert_eq!(deposit.data[16..36], token.as_bytes()[..]);
+    let withdraw = registry
+      .router_withdraw(&Asset::Ethereum, &ci, recipient, 7)
+      .unwrap();
+    assert_eq!(withdraw.data[..4], selector(ROUTER_WITHDRAW));
+    assert_eq!(withdraw.data[4..36], [0; 32]);
+    assert_eq!(
+      registry.router_withdraw(&Asset::Tether, &Network::Mainnet, recipient, 7),
+      Asset::Tether.router_withdraw(&Network::Mainnet, recipient, 7)
+    );
+  }
+
+  #[test]
+  fn test_serde() {
+    let address = EvmAddress::from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+    let json = serde_json::to_string(&address).unwrap();
+    assert_eq!(json, r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed""#);
+    assert_eq!(serde_json::from_str::<EvmAddress>(&json).unwrap(), address);
+    assert!(
+      serde_json::from_str::<EvmAddress>(r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD""#)
+        .is_err()
+    );
+  }
+}
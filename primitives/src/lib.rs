@@ -18,7 +18,7 @@
 
 use crate::assets::Asset;
 use codec::{Decode, Encode, MaxEncodedLen};
-use scale_info::{prelude::string::String, TypeInfo};
+use scale_info::TypeInfo;
 use sp_runtime::{
   generic,
   traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -26,14 +26,20 @@ use sp_runtime::{
 };
 pub use swap::*;
 
+pub mod amount;
 pub mod assets;
+pub mod evm;
 mod swap;
 
+#[cfg(feature = "std")]
+pub mod address;
 #[cfg(feature = "std")]
 pub mod networks;
+#[cfg(feature = "std")]
+pub mod serde_balance;
 
 #[cfg(feature = "std")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -161,6 +167,7 @@ pub struct WatchList<BlockNumber, BoundedString> {
   /// The Asset ID to watch.
   pub currency_id: CurrencyId,
   /// The amount of the action.
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount: Balance,
   /// The transaction ID on the origin chain.
   pub transaction_id: BoundedString,
@@ -230,8 +237,10 @@ pub struct Withdrawal<AccountId, BlockNumber, BoundedString> {
   /// The Asset ID to widthdraw.
   pub asset_id: CurrencyId,
   /// The amount of the asset to widthdraw.
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount: Balance,
-  /// The address on the AssetID chain where to send the funds.
+  /// The address on the AssetID chain where to send the funds, see
+  /// `Asset::validate_external_address`.
   pub external_address: BoundedString,
   /// The block ID the withdrawal has been initialized.
   pub block_number: BlockNumber,
@@ -247,8 +256,13 @@ pub struct Mint<AccountId, BoundedString> {
   /// The Asset ID to mint
   pub currency_id: CurrencyId,
   /// The amount of `CurrencyId` to mint    
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub mint_amount: Balance,
   /// The amount of `CurrencyId` paid in gas fee for the deposit
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(default, with = "crate::serde_balance::option")
+  )]
   pub gas_amount: Option<Balance>,
   /// The transaction ID on chain in bytes
   pub transaction_id: BoundedString,
@@ -272,8 +286,28 @@ pub struct Stake<Balance, BlockNumber> {
   /// Not saving the timestamp will same some space on-chain.
   pub initial_block: BlockNumber,
   /// Initial balance
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub initial_balance: Balance,
   /// Principal balance (with accrued interest)
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub principal: Balance,
   /// Duration of the stake
   pub duration: BlockNumber,
@@ -287,8 +321,28 @@ pub struct Stake<Balance, BlockNumber> {
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct StakeCurrencyMeta<Balance> {
   /// Minimum stake amount for this currency.
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub minimum_amount: Balance,
   /// Maximum stake amount for this currency.
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub maximum_amount: Balance,
 }
 
@@ -350,10 +404,13 @@ pub struct ActiveEraInfo<BlockNumber> {
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct Fee {
   /// Total amount before fees
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount: Balance,
   /// The fees at the moment of the transaction
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub fee: Balance,
   /// The fees at the moment of the transaction in TDFY's
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub fee_tdfy: Balance,
 }
 
@@ -365,10 +422,12 @@ pub struct SunriseSwapPool {
   /// Sunrise pool unique identifier
   pub id: u8,
   /// Minimum size of the transaction before fees in TDFY's
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub minimum_tdfy_value: Balance,
   /// Transactions remaining: For each tier, there is a maximum number of transactions allocated to that tier
   pub transactions_remaining: u32,
   /// The amount of TDFY remaining in the pool
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub balance: Balance,
   /// The fixed point number from 0..to max.
   /// Unlike `Percentage` it can be more than 1.
@@ -381,8 +440,10 @@ pub struct SunriseSwapPool {
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct OnboardingRebates {
   /// Initial pool size
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub initial_amount: Balance,
   /// Available pool size
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub available_amount: Balance,
 }
 
@@ -392,8 +453,28 @@ pub struct OnboardingRebates {
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct CurrencyBalance<Balance> {
   /// Available balance
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub available: Balance,
   /// Reserved balance
+  #[cfg_attr(
+    feature = "balance-as-string",
+    serde(
+      with = "crate::serde_balance",
+      bound(
+        serialize = "Balance: core::fmt::Display",
+        deserialize = "Balance: core::str::FromStr"
+      )
+    )
+  )]
   pub reserved: Balance,
 }
 
@@ -407,7 +488,8 @@ pub mod pallet {
   pub trait QuorumExt<AccountId, BlockNumber> {
     /// Get current Quorum status.
     fn is_quorum_enabled() -> bool;
-    /// Add a new withdrawl to the queue.
+    /// Add a new withdrawl to the queue, the `external_address` should have been validated
+    /// with `Asset::validate_external_address` first.
     fn add_new_withdrawal_in_queue(
       account_id: AccountId,
       asset_id: CurrencyId,
@@ -543,33 +625,6 @@ pub mod pallet {
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct BalanceInfo {
-  #[cfg_attr(
-    feature = "std",
-    serde(bound(serialize = "Balance: std::fmt::Display"))
-  )]
-  #[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
-  #[cfg_attr(
-    feature = "std",
-    serde(bound(deserialize = "Balance: std::str::FromStr"))
-  )]
-  #[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+  #[cfg_attr(feature = "std", serde(with = "serde_balance"))]
   pub amount: Balance,
 }
-
-// Serializable Balance
-#[cfg(feature = "std")]
-fn serialize_as_string<S: Serializer, T: std::fmt::Display>(
-  t: &T,
-  serializer: S,
-) -> core::result::Result<S::Ok, S::Error> {
-  serializer.serialize_str(&t.to_string())
-}
-
-#[cfg(feature = "std")]
-fn deserialize_from_string<'de, D: Deserializer<'de>, T: std::str::FromStr>(
-  deserializer: D,
-) -> core::result::Result<T, D::Error> {
-  let s = String::deserialize(deserializer)?;
-  s.parse::<T>()
-    .map_err(|_| serde::de::Error::custom("Parse from string failed"))
-}
@@ -14,41 +14,176 @@
 // You should have received a copy of the GNU General Public License
 // along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::assets::Asset;
+use crate::{
+  assets::{Asset, ParseAssetError},
+  evm::EvmAddress,
+  AccountId, AssetId, BlockNumber, Timestamp,
+};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, str::FromStr};
+use sp_core::{
+  crypto::{Ss58AddressFormat, Ss58Codec},
+  H256,
+};
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, EnumIter, Debug, Serialize, Deserialize, Hash)]
+/// Serialized with its lowercase name, e.g.: `"mainnet"` or `"custom:ci"`. Deserialized ignoring
+/// the case, so the capitalized names serialized before, e.g. `"Mainnet"`, are still accepted.
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Hash)]
 pub enum Network {
   Local,
   Devnet,
   Staging,
   Testnet,
   Mainnet,
+  /// A private network, e.g. a throwaway CI chain, its deployments are provided to the
+  /// `NetworkRegistry` with an overlay.
+  Custom(String),
 }
 
-pub type Addresses = BTreeMap<String, String>;
+pub type Addresses = BTreeMap<Network, EvmAddress>;
 
-pub type ChainIds = BTreeMap<String, u32>;
+pub type ChainIds = BTreeMap<Network, u32>;
 
-pub type Enabled = BTreeMap<String, bool>;
+pub type Enabled = BTreeMap<Network, bool>;
 
-impl FromStr for Network {
+pub type Schedules = BTreeMap<Network, Schedule>;
+
+/// Bound of a schedule, serialized as `{ "block": 15000000 }` or `{ "timestamp": 1672531200000 }`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchedulePoint {
+  Block(BlockNumber),
+  /// Milliseconds since the UNIX epoch.
+  Timestamp(Timestamp),
+}
+
+impl FromStr for SchedulePoint {
   type Err = String;
+
+  /// Parse `block(<number>)` or `timestamp(<milliseconds>)`, as declared on the assets.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || {
+      format!(
+        "`{}` is not a schedule point, expected `block(<number>)` or `timestamp(<milliseconds>)`",
+        s
+      )
+    };
+    let (kind, value) = s
+      .strip_suffix(')')
+      .and_then(|s| s.split_once('('))
+      .ok_or_else(invalid)?;
+    match kind {
+      "block" => value.parse().map(Self::Block).map_err(|_| invalid()),
+      "timestamp" => value.parse().map(Self::Timestamp).map_err(|_| invalid()),
+      _ => Err(invalid()),
+    }
+  }
+}
+
+impl std::fmt::Display for SchedulePoint {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Block(block) => write!(f, "block({})", block),
+      Self::Timestamp(timestamp) => write!(f, "timestamp({})", timestamp),
+    }
+  }
+}
+
+impl SchedulePoint {
+  /// Compare points of the same kind, a block and a timestamp aren't comparable.
+  fn partial_cmp(&self, other: &SchedulePoint) -> Option<core::cmp::Ordering> {
+    match (self, other) {
+      (Self::Block(a), Self::Block(b)) => Some(a.cmp(b)),
+      (Self::Timestamp(a), Self::Timestamp(b)) => Some(a.cmp(b)),
+      _ => None,
+    }
+  }
+}
+
+/// When an enabled asset is listed or delisted on a network.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+  /// First point the asset is enabled at, enabled since the genesis when `None`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub enabled_from: Option<SchedulePoint>,
+  /// First point the asset is disabled at, never disabled when `None`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub disabled_at: Option<SchedulePoint>,
+}
+
+impl Schedule {
+  /// Return whether the schedule is active at the point, or `None` when a bound is of the
+  /// other kind: a block can't be compared with a timestamp, so the schedule of an asset
+  /// listed at a block can only be evaluated at a block.
+  pub fn is_active_at(&self, at: SchedulePoint) -> Option<bool> {
+    use core::cmp::Ordering;
+    let started = match self.enabled_from {
+      Some(from) => at.partial_cmp(&from)? != Ordering::Less,
+      None => true,
+    };
+    let ended = match self.disabled_at {
+      Some(to) => at.partial_cmp(&to)? != Ordering::Less,
+      None => false,
+    };
+    Some(started && !ended)
+  }
+}
+
+static BUILTIN_NETWORKS: [Network; 5] = [
+  Network::Local,
+  Network::Devnet,
+  Network::Staging,
+  Network::Testnet,
+  Network::Mainnet,
+];
+
+/// Iterate over the built-in networks, custom networks are unknown to this crate.
+impl IntoEnumIterator for Network {
+  type Iterator = core::iter::Cloned<core::slice::Iter<'static, Network>>;
+  fn iter() -> Self::Iterator {
+    BUILTIN_NETWORKS.iter().cloned()
+  }
+}
+
+/// Error returned when a string isn't a valid network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNetworkError {
+  /// The string which failed to parse.
+  pub input: String,
+}
+
+impl std::fmt::Display for ParseNetworkError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "invalid network `{}`, expected one of: ", self.input)?;
+    for network in Network::iter() {
+      write!(f, "{}, ", network)?;
+    }
+    write!(f, "custom:<name>")
+  }
+}
+
+impl std::error::Error for ParseNetworkError {}
+
+impl FromStr for Network {
+  type Err = ParseNetworkError;
+  /// Parse a network from its name ignoring the case, custom networks are prefixed by `custom:`.
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    if s.to_lowercase() == "local" {
-      return Ok(Network::Local);
-    } else if s.to_lowercase() == "devnet" {
-      return Ok(Network::Devnet);
-    } else if s.to_lowercase() == "staging" {
-      return Ok(Network::Staging);
-    } else if s.to_lowercase() == "testnet" {
-      return Ok(Network::Testnet);
-    } else if s.to_lowercase() == "mainnet" {
-      return Ok(Network::Mainnet);
+    if let Some(network) =
+      Network::iter().find(|network| network.to_string().eq_ignore_ascii_case(s))
+    {
+      return Ok(network);
+    }
+    match s.split_once(':') {
+      Some((prefix, name)) if prefix.eq_ignore_ascii_case("custom") && !name.is_empty() => {
+        Ok(Network::Custom(name.to_string()))
+      }
+      _ => Err(ParseNetworkError {
+        input: s.to_string(),
+      }),
     }
-    Err("invalid network string".to_string())
   }
 }
 
@@ -60,142 +195,1103 @@ impl std::fmt::Display for Network {
       Self::Staging => write!(f, "staging"),
       Self::Testnet => write!(f, "testnet"),
       Self::Mainnet => write!(f, "mainnet"),
+      Self::Custom(name) => write!(f, "custom:{}", name),
+    }
+  }
+}
+
+impl Serialize for Network {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Network {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let network = String::deserialize(deserializer)?;
+    network.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// SS58 prefix registered for Tidechain, see the `ss58-registry`.
+pub const TIDEFI_SS58_PREFIX: u16 = 7007;
+
+/// SS58 prefix of generic Substrate chains.
+pub const SUBSTRATE_SS58_PREFIX: u16 = 42;
+
+impl Network {
+  /// Return the SS58 prefix of the accounts on the network, only mainnet uses the
+  /// registered Tidechain prefix.
+  pub const fn ss58_prefix(&self) -> u16 {
+    match self {
+      Self::Mainnet => TIDEFI_SS58_PREFIX,
+      Self::Local | Self::Devnet | Self::Staging | Self::Testnet | Self::Custom(_) => {
+        SUBSTRATE_SS58_PREFIX
+      }
+    }
+  }
+
+  /// Return the genesis hash a node of the network is expected to report, `None` when the
+  /// chain is regenerated on reset or its genesis isn't pinned in this crate.
+  pub fn genesis_hash(&self) -> Option<H256> {
+    match self {
+      Self::Local => None,
+      Self::Devnet => None,
+      Self::Staging => None,
+      Self::Testnet => None,
+      Self::Mainnet => None,
+      Self::Custom(_) => None,
+    }
+  }
+
+  /// Return the default HTTP RPC endpoints of the network, `None` when they aren't pinned in
+  /// this crate.
+  pub const fn rpc_endpoints(&self) -> Option<&'static [&'static str]> {
+    match self {
+      Self::Local => Some(&["http://127.0.0.1:9933"]),
+      Self::Devnet => None,
+      Self::Staging => None,
+      Self::Testnet => None,
+      Self::Mainnet => None,
+      Self::Custom(_) => None,
+    }
+  }
+
+  /// Return the default WebSocket RPC endpoints of the network, `None` when they aren't pinned
+  /// in this crate.
+  pub const fn ws_endpoints(&self) -> Option<&'static [&'static str]> {
+    match self {
+      Self::Local => Some(&["ws://127.0.0.1:9944"]),
+      Self::Devnet => None,
+      Self::Staging => None,
+      Self::Testnet => None,
+      Self::Mainnet => None,
+      Self::Custom(_) => None,
+    }
+  }
+
+  /// Return the block explorer URL template of the network, `{}` is replaced by a block
+  /// hash or number.
+  pub const fn explorer(&self) -> Option<&'static str> {
+    match self {
+      Self::Local => {
+        Some("https://polkadot.js.org/apps/?rpc=ws%3A%2F%2F127.0.0.1%3A9944#/explorer/query/{}")
+      }
+      Self::Devnet => None,
+      Self::Staging => None,
+      Self::Testnet => None,
+      Self::Mainnet => None,
+      Self::Custom(_) => None,
+    }
+  }
+
+  /// Return the block explorer URL of a block hash or number.
+  pub fn explorer_url(&self, block: impl std::fmt::Display) -> Option<String> {
+    self
+      .explorer()
+      .map(|template| template.replace("{}", &block.to_string()))
+  }
+
+  /// Encode the account with the SS58 prefix of the network.
+  pub fn encode_account(&self, account: &AccountId) -> String {
+    account.to_ss58check_with_version(Ss58AddressFormat::custom(self.ss58_prefix()))
+  }
+
+  /// Decode an SS58 address, which must use the prefix of the network.
+  pub fn decode_account(&self, address: &str) -> Result<AccountId, Ss58Error> {
+    let (account, format) =
+      AccountId::from_ss58check_with_version(address).map_err(|_| Ss58Error::Invalid)?;
+    let prefix = u16::from(format);
+    if prefix != self.ss58_prefix() {
+      return Err(Ss58Error::UnexpectedPrefix {
+        expected: self.ss58_prefix(),
+        found: prefix,
+      });
+    }
+    Ok(account)
+  }
+}
+
+/// Bitcoin network a Tidechain network talks to.
+#[derive(Clone, Copy, PartialEq, Eq, EnumIter, Debug, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinNetwork {
+  Mainnet,
+  Testnet,
+  Signet,
+  Regtest,
+}
+
+impl BitcoinNetwork {
+  /// Return the base58 version byte of pay to public key hash addresses.
+  pub const fn p2pkh_version(&self) -> u8 {
+    match self {
+      Self::Mainnet => 0x00,
+      Self::Testnet | Self::Signet | Self::Regtest => 0x6f,
+    }
+  }
+
+  /// Return the base58 version byte of pay to script hash addresses.
+  pub const fn p2sh_version(&self) -> u8 {
+    match self {
+      Self::Mainnet => 0x05,
+      Self::Testnet | Self::Signet | Self::Regtest => 0xc4,
+    }
+  }
+
+  /// Return the human readable part of segwit addresses.
+  pub const fn bech32_hrp(&self) -> &'static str {
+    match self {
+      Self::Mainnet => "bc",
+      Self::Testnet | Self::Signet => "tb",
+      Self::Regtest => "bcrt",
+    }
+  }
+}
+
+impl std::fmt::Display for BitcoinNetwork {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Mainnet => write!(f, "mainnet"),
+      Self::Testnet => write!(f, "testnet"),
+      Self::Signet => write!(f, "signet"),
+      Self::Regtest => write!(f, "regtest"),
+    }
+  }
+}
+
+impl Network {
+  /// Return the Bitcoin network the deposits and withdrawals of BTC are made on.
+  pub const fn bitcoin_network(&self) -> BitcoinNetwork {
+    match self {
+      Self::Mainnet => BitcoinNetwork::Mainnet,
+      Self::Devnet | Self::Staging | Self::Testnet => BitcoinNetwork::Testnet,
+      Self::Local | Self::Custom(_) => BitcoinNetwork::Regtest,
+    }
+  }
+
+  /// Return the number of confirmations a Bitcoin deposit requires before it's credited.
+  pub const fn bitcoin_confirmations(&self) -> u32 {
+    match self {
+      Self::Mainnet => 6,
+      Self::Staging | Self::Testnet => 3,
+      Self::Local | Self::Devnet | Self::Custom(_) => 1,
+    }
+  }
+}
+
+/// Error returned when a string isn't an SS58 address of the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ss58Error {
+  /// The string isn't a valid SS58 address.
+  Invalid,
+  /// The address belongs to a network with another prefix.
+  UnexpectedPrefix { expected: u16, found: u16 },
+}
+
+impl std::fmt::Display for Ss58Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Invalid => write!(f, "invalid SS58 address"),
+      Self::UnexpectedPrefix { expected, found } => {
+        write!(f, "expected SS58 prefix {}, found {}", expected, found)
+      }
+    }
+  }
+}
+
+impl std::error::Error for Ss58Error {}
+
+/// Deployment of an asset on a network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+  /// EVM chain id.
+  pub chain_id: Option<u32>,
+  /// Router contract address.
+  pub router: Option<EvmAddress>,
+  /// Multisig wallet address.
+  pub multisig: Option<EvmAddress>,
+  /// Token contract address.
+  pub address: Option<EvmAddress>,
+  /// Whether the asset is live on the network.
+  pub enabled: bool,
+  /// First point the asset is enabled at.
+  pub enabled_from: Option<SchedulePoint>,
+  /// First point the asset is disabled at.
+  pub disabled_at: Option<SchedulePoint>,
+}
+
+impl Deployment {
+  /// Return when the asset is listed or delisted on the network.
+  pub fn schedule(&self) -> Schedule {
+    Schedule {
+      enabled_from: self.enabled_from,
+      disabled_at: self.disabled_at,
+    }
+  }
+}
+
+impl Default for Deployment {
+  fn default() -> Self {
+    // same as declaring `#[asset::network(name)]` without `enabled`
+    Self {
+      chain_id: None,
+      router: None,
+      multisig: None,
+      address: None,
+      enabled: true,
+      enabled_from: None,
+      disabled_at: None,
+    }
+  }
+}
+
+/// Fields of a `Deployment` read from an overlay, the missing ones are left untouched.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DeploymentOverlay {
+  #[serde(alias = "chain_id")]
+  chain_id: Option<u32>,
+  router: Option<EvmAddress>,
+  multisig: Option<EvmAddress>,
+  address: Option<EvmAddress>,
+  enabled: Option<bool>,
+  #[serde(alias = "enabled_from")]
+  enabled_from: Option<SchedulePoint>,
+  #[serde(alias = "disabled_at")]
+  disabled_at: Option<SchedulePoint>,
+}
+
+impl DeploymentOverlay {
+  fn apply(self, deployment: &mut Deployment) {
+    if let Some(chain_id) = self.chain_id {
+      deployment.chain_id = Some(chain_id);
+    }
+    if let Some(router) = self.router {
+      deployment.router = Some(router);
+    }
+    if let Some(multisig) = self.multisig {
+      deployment.multisig = Some(multisig);
+    }
+    if let Some(address) = self.address {
+      deployment.address = Some(address);
+    }
+    if let Some(enabled) = self.enabled {
+      deployment.enabled = enabled;
+    }
+    if let Some(enabled_from) = self.enabled_from {
+      deployment.enabled_from = Some(enabled_from);
+    }
+    if let Some(disabled_at) = self.disabled_at {
+      deployment.disabled_at = Some(disabled_at);
+    }
+  }
+}
+
+/// Overlay file, deployments by asset symbol then by network:
+///
+/// ```toml
+/// [ETH.local]
+/// chainId = 1337
+/// router = "0x…"
+///
+/// [USDT.local]
+/// address = "0x…"
+/// enabledFrom = { block = 1000 }
+/// ```
+type Overlay = BTreeMap<String, BTreeMap<String, DeploymentOverlay>>;
+
+#[derive(Debug)]
+pub enum RegistryError {
+  /// The overlay file can't be read.
+  Io(std::io::Error),
+  /// The overlay file isn't valid JSON or TOML.
+  Format(String),
+  /// The overlay file extension is neither `json` nor `toml`.
+  UnsupportedFormat(String),
+  UnknownAsset(ParseAssetError),
+  UnknownNetwork(ParseNetworkError),
+  /// An environment variable has an unknown field or an invalid value.
+  InvalidVariable {
+    name: String,
+    reason: String,
+  },
+  /// The schedule bounds are of different kinds or out of order.
+  InvalidSchedule {
+    symbol: String,
+    network: Network,
+  },
+}
+
+impl std::fmt::Display for RegistryError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Io(error) => write!(f, "unable to read the overlay: {}", error),
+      Self::Format(error) => write!(f, "invalid overlay: {}", error),
+      Self::UnsupportedFormat(path) => write!(
+        f,
+        "unsupported overlay `{}`, expected a `.json` or `.toml` file",
+        path
+      ),
+      Self::UnknownAsset(error) => write!(f, "{}", error),
+      Self::UnknownNetwork(error) => write!(f, "{}", error),
+      Self::InvalidVariable { name, reason } => write!(f, "invalid `{}`: {}", name, reason),
+      Self::InvalidSchedule { symbol, network } => write!(
+        f,
+        "{} on {} must be enabled before being disabled, at blocks or timestamps",
+        symbol, network
+      ),
+    }
+  }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// A registry entry which is valid, but likely not what was intended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryWarning {
+  /// An enabled token has no contract address on the network, no call can be built for it.
+  MissingAddress { symbol: String, network: Network },
+}
+
+impl std::fmt::Display for RegistryWarning {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::MissingAddress { symbol, network } => {
+        write!(f, "{} is enabled on {} without an address", symbol, network)
+      }
     }
   }
 }
 
-impl Asset {
-  pub fn chain_id(&self) -> Option<ChainIds> {
-    if self == &Asset::Ethereum {
-      let mut ids = BTreeMap::new();
-      ids.insert("local".to_string(), 1337);
-      ids.insert("devnet".to_string(), 5);
-      ids.insert("staging".to_string(), 5);
-      ids.insert("testnet".to_string(), 5);
-      ids.insert("mainnet".to_string(), 1);
-      return Some(ids);
-    }
-    None
-  }
-
-  pub fn router(&self) -> Option<Addresses> {
-    if self == &Asset::Ethereum {
-      return Some(str_map(vec![
-        ("local", "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512"),
-        ("devnet", "0x3b446e2eeb7a8171bd3a41452b22971e7d17aa80"),
-        ("staging", "0xeef73e2294d48004c2b06a156723f5c21495eeb1"),
-        ("testnet", "0xa16ccb4c3911be7c6adc115ec5e529f5ab0abf9d"),
-        ("mainnet", "0x8f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c69729"),
-      ]));
-    }
-    None
-  }
-
-  pub fn multisig(&self) -> Option<Addresses> {
-    if self == &Asset::Ethereum {
-      return Some(str_map(vec![
-        ("local", "0x5fc8d32690cc91d4c39d9d3abcbd16989f875707"),
-        ("devnet", "0x22f3f691392c1d6c2c96b2333f08ccf0354f97b4"),
-        ("staging", "0x87f935d2e08755eafad5d8f199914d7ba8228862"),
-        ("testnet", "0x2d6d7a1715470d0377ed038f5674f76fbfa0db75"),
-        ("mainnet", "0xff9d5585592507eff86d76cd9134a78e69786aa3"),
-      ]));
-    }
-    None
-  }
-
-  pub fn address(&self) -> Option<Addresses> {
-    if self == &Asset::Tether {
-      return Some(str_map(vec![
-        ("local", "0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0"),
-        ("devnet", "0x3fcbcc5df304cebfc3804dc8e70addf60cb05a1b"),
-        ("staging", "0x348484e4a9a95dbd667398fe4f4fa6d4aaae4e18"),
-        ("testnet", "0x74f8f1ba33f7def42ad29aba793c69497e512d2d"),
-        ("mainnet", "0xdAC17F958D2ee523a2206206994597C13D831ec7"),
-      ]));
-    } else if self == &Asset::USDCoin {
-      return Some(str_map(vec![
-        ("local", "0xa513e6e4b8f2a923d98304ec87f64353c4d5c853"),
-        ("devnet", "0x71819a038e02c521db8005936b3883cecfd886c0"),
-        ("staging", "0x34c7391130c375fbbef15d8bc16907f001ad8cbd"),
-        ("testnet", "0xbce2733e4b0eb15278ebb9f8496d3c638d1f43dd"),
-        ("mainnet", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
-      ]));
-    } else if self == &Asset::AllTimeHigh {
-      return Some(str_map(vec![
-        ("local", "0x8a791620dd6260079bf849dc5567adc3f2fdc318"),
-        ("testnet", "0xa6bf2a2181ef0779e86e73852f344d6cc52661ca"),
-        ("mainnet", "0x527c41d75bae7f992ae1d3179621a29df9255dc4"),
-      ]));
-    } else if self == &Asset::Tdfy {
-      return Some(str_map(vec![(
-        "local",
-        "0x610178da211fef7d417bc0e6fed39f05609ad788",
-      )]));
-    }
-    None
-  }
-
-  pub fn enabled(&self) -> Enabled {
-    match self {
-      Asset::Tdfy => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", true),
-        ("mainnet", true),
-      ]),
-      Asset::Bitcoin => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", true),
-        ("mainnet", true),
-      ]),
-      Asset::Ethereum => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", true),
-        ("mainnet", true),
-      ]),
-      Asset::Tether => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", false),
-        ("mainnet", false),
-      ]),
-      Asset::USDCoin => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", true),
-        ("mainnet", true),
-      ]),
-      Asset::AllTimeHigh => bool_map(vec![
-        ("local", true),
-        ("devnet", true),
-        ("staging", true),
-        ("testnet", true),
-        ("mainnet", true),
-      ]),
-    }
-  }
-}
-
-fn str_map(inp: Vec<(&str, &str)>) -> BTreeMap<String, String> {
-  let mut r = BTreeMap::new();
-  for (one, two) in inp {
-    r.insert(one.to_string(), two.to_string());
-  }
-  r
-}
-
-fn bool_map(inp: Vec<(&str, bool)>) -> BTreeMap<String, bool> {
-  let mut r = BTreeMap::new();
-  for (one, two) in inp {
-    r.insert(one.to_string(), two);
-  }
-  r
+/// Network deployments of every asset, the built-in tables can be overlaid
+/// at runtime to point at freshly deployed contracts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkRegistry {
+  deployments: BTreeMap<AssetId, BTreeMap<Network, Deployment>>,
+}
+
+impl Default for NetworkRegistry {
+  fn default() -> Self {
+    Self::builtin()
+  }
+}
+
+impl NetworkRegistry {
+  /// Prefix of the environment variables read by `overlay_env`.
+  pub const ENV_PREFIX: &'static str = "TIDEFI_";
+
+  /// Registry of the deployments declared on `Asset`.
+  pub fn builtin() -> Self {
+    let deployments = Asset::iter()
+      .map(|asset| {
+        let chain_ids = asset.chain_id().unwrap_or_default();
+        let routers = asset.router().unwrap_or_default();
+        let multisigs = asset.multisig().unwrap_or_default();
+        let addresses = asset.address().unwrap_or_default();
+        let mut schedules = asset.schedule();
+        let networks = asset
+          .enabled()
+          .into_iter()
+          .map(|(network, enabled)| {
+            let schedule = schedules.remove(&network).unwrap_or_default();
+            let deployment = Deployment {
+              chain_id: chain_ids.get(&network).copied(),
+              router: routers.get(&network).copied(),
+              multisig: multisigs.get(&network).copied(),
+              address: addresses.get(&network).copied(),
+              enabled,
+              enabled_from: schedule.enabled_from,
+              disabled_at: schedule.disabled_at,
+            };
+            (network, deployment)
+          })
+          .collect();
+        (asset.id(), networks)
+      })
+      .collect();
+    Self { deployments }
+  }
+
+  /// Overlay the deployments of a `.json` or `.toml` file.
+  pub fn overlay_file(&mut self, path: impl AsRef<Path>) -> Result<(), RegistryError> {
+    let path = path.as_ref();
+    let overlay = match path.extension().and_then(|extension| extension.to_str()) {
+      Some("json") => Self::overlay_json,
+      Some("toml") => Self::overlay_toml,
+      _ => return Err(RegistryError::UnsupportedFormat(path.display().to_string())),
+    };
+    let content = std::fs::read_to_string(path).map_err(RegistryError::Io)?;
+    overlay(self, &content)
+  }
+
+  /// Overlay the deployments of a JSON document, e.g.:
+  /// `{ "ETH": { "local": { "chainId": 1337, "router": "0x…" } } }`.
+  pub fn overlay_json(&mut self, content: &str) -> Result<(), RegistryError> {
+    let overlay: Overlay =
+      serde_json::from_str(content).map_err(|error| RegistryError::Format(error.to_string()))?;
+    self.overlay(overlay)
+  }
+
+  /// Overlay the deployments of a TOML document, e.g.:
+  /// `[ETH.local]` followed by `chainId = 1337`.
+  pub fn overlay_toml(&mut self, content: &str) -> Result<(), RegistryError> {
+    let overlay: Overlay =
+      toml::from_str(content).map_err(|error| RegistryError::Format(error.to_string()))?;
+    self.overlay(overlay)
+  }
+
+  /// Overlay the deployments of the process environment, see `overlay_vars`.
+  pub fn overlay_env(&mut self) -> Result<(), RegistryError> {
+    self.overlay_vars(std::env::vars())
+  }
+
+  /// Overlay the deployments of `TIDEFI_<NETWORK>_<SYMBOL>_<FIELD>` variables,
+  /// where the field is one of `CHAIN_ID`, `ROUTER`, `MULTISIG`, `ADDRESS`, `ENABLED`,
+  /// `ENABLED_FROM` or `DISABLED_AT`, e.g.: `TIDEFI_LOCAL_ETH_ROUTER=0x…` or
+  /// `TIDEFI_MAINNET_USDT_ENABLED_FROM=block(1000)`. A custom network is addressed with
+  /// `CUSTOM_<NAME>` and its lowercase name, e.g.: `TIDEFI_CUSTOM_CI_ETH_ROUTER` for `custom:ci`.
+  ///
+  /// Variables which don't start with the prefix followed by a network are ignored.
+  pub fn overlay_vars<I>(&mut self, vars: I) -> Result<(), RegistryError>
+  where
+    I: IntoIterator<Item = (String, String)>,
+  {
+    for (name, value) in vars {
+      let rest = match name.strip_prefix(Self::ENV_PREFIX) {
+        Some(rest) => rest,
+        None => continue,
+      };
+      let (network, rest) = match rest.split_once('_') {
+        Some(("CUSTOM", rest)) => {
+          let (custom, rest) = rest.split_once('_').unwrap_or((rest, ""));
+          (Network::Custom(custom.to_lowercase()), rest)
+        }
+        Some((network, rest)) => match Network::from_str(network) {
+          Ok(network) => (network, rest),
+          Err(_) => continue,
+        },
+        None => continue,
+      };
+      let invalid = |reason: String| RegistryError::InvalidVariable {
+        name: name.clone(),
+        reason,
+      };
+      let is_unnamed = matches!(&network, Network::Custom(custom) if custom.is_empty());
+      let (symbol, field) = match rest.split_once('_') {
+        Some(parts) if !is_unnamed => parts,
+        _ => {
+          return Err(invalid(
+            "expected `TIDEFI_<NETWORK>_<SYMBOL>_<FIELD>`".to_string(),
+          ))
+        }
+      };
+      let asset = Asset::from_str(symbol).map_err(RegistryError::UnknownAsset)?;
+
+      let mut overlay = DeploymentOverlay::default();
+      match field {
+        "CHAIN_ID" => {
+          overlay.chain_id = Some(
+            value
+              .parse()
+              .map_err(|_| invalid(format!("`{}` is not a valid chain id", value)))?,
+          )
+        }
+        "ROUTER" | "MULTISIG" | "ADDRESS" => {
+          let address = value
+            .parse()
+            .map_err(|error| invalid(format!("`{}` is not a valid address: {}", value, error)))?;
+          match field {
+            "ROUTER" => overlay.router = Some(address),
+            "MULTISIG" => overlay.multisig = Some(address),
+            _ => overlay.address = Some(address),
+          }
+        }
+        "ENABLED" => {
+          overlay.enabled = Some(
+            value
+              .parse()
+              .map_err(|_| invalid(format!("`{}` is not `true` or `false`", value)))?,
+          )
+        }
+        "ENABLED_FROM" => overlay.enabled_from = Some(value.parse().map_err(invalid)?),
+        "DISABLED_AT" => overlay.disabled_at = Some(value.parse().map_err(invalid)?),
+        _ => {
+          return Err(invalid(format!(
+            "unknown field `{}`, expected one of: CHAIN_ID, ROUTER, MULTISIG, ADDRESS, ENABLED, \
+             ENABLED_FROM, DISABLED_AT",
+            field
+          )))
+        }
+      }
+      overlay.apply(self.entry(&asset, network));
+    }
+    Ok(())
+  }
+
+  fn overlay(&mut self, overlay: Overlay) -> Result<(), RegistryError> {
+    for (symbol, networks) in overlay {
+      let asset = Asset::from_str(&symbol).map_err(RegistryError::UnknownAsset)?;
+      for (network, deployment) in networks {
+        let network = Network::from_str(&network).map_err(RegistryError::UnknownNetwork)?;
+        deployment.apply(self.entry(&asset, network));
+      }
+    }
+    Ok(())
+  }
+
+  fn entry(&mut self, asset: &Asset, network: Network) -> &mut Deployment {
+    self
+      .deployments
+      .entry(asset.id())
+      .or_default()
+      .entry(network)
+      .or_default()
+  }
+
+  /// Ensure every schedule is enabled before being disabled, and warn about every enabled token,
+  /// an asset living on the chain of another asset, without a contract address.
+  pub fn validate(&self) -> Result<Vec<RegistryWarning>, RegistryError> {
+    for asset in Asset::iter() {
+      for (network, deployment) in self.deployments.get(&asset.id()).into_iter().flatten() {
+        if let (Some(from), Some(to)) = (deployment.enabled_from, deployment.disabled_at) {
+          if from.partial_cmp(&to) != Some(core::cmp::Ordering::Less) {
+            return Err(RegistryError::InvalidSchedule {
+              symbol: asset.symbol(),
+              network: network.clone(),
+            });
+          }
+        }
+      }
+    }
+    let mut warnings = Vec::new();
+    for asset in Asset::iter().filter(|asset| asset.base_chain().is_some()) {
+      for (network, deployment) in self.deployments.get(&asset.id()).into_iter().flatten() {
+        if deployment.enabled && deployment.address.is_none() {
+          warnings.push(RegistryWarning::MissingAddress {
+            symbol: asset.symbol(),
+            network: network.clone(),
+          });
+        }
+      }
+    }
+    Ok(warnings)
+  }
+
+  /// Return the deployment of the asset on the network, if declared.
+  pub fn deployment(&self, asset: &Asset, network: &Network) -> Option<&Deployment> {
+    self.deployments.get(&asset.id())?.get(network)
+  }
+
+  /// Return the EVM chain id of the asset on the network.
+  pub fn chain_id(&self, asset: &Asset, network: &Network) -> Option<u32> {
+    self.deployment(asset, network)?.chain_id
+  }
+
+  /// Return the router contract address of the asset on the network.
+  pub fn router(&self, asset: &Asset, network: &Network) -> Option<EvmAddress> {
+    self.deployment(asset, network)?.router
+  }
+
+  /// Return the multisig wallet address of the asset on the network.
+  pub fn multisig(&self, asset: &Asset, network: &Network) -> Option<EvmAddress> {
+    self.deployment(asset, network)?.multisig
+  }
+
+  /// Return the token contract address of the asset on the network.
+  pub fn address(&self, asset: &Asset, network: &Network) -> Option<EvmAddress> {
+    self.deployment(asset, network)?.address
+  }
+
+  /// Return whether the asset is enabled on the network, `false` when it isn't declared.
+  pub fn enabled(&self, asset: &Asset, network: &Network) -> bool {
+    self
+      .deployment(asset, network)
+      .map(|deployment| deployment.enabled)
+      .unwrap_or_default()
+  }
+
+  /// Return whether the asset is enabled on the network at a block or a timestamp, or `None`
+  /// when its schedule is bounded by the other kind of point, see `Schedule::is_active_at`.
+  /// Delisted assets are never enabled, whatever their overlaid deployments.
+  pub fn is_enabled_at(&self, asset: &Asset, network: &Network, at: SchedulePoint) -> Option<bool> {
+    match self.deployment(asset, network) {
+      Some(deployment) if deployment.enabled && asset.lifecycle().is_active() => {
+        deployment.schedule().is_active_at(at)
+      }
+      _ => Some(false),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn address(address: &str) -> EvmAddress {
+    address.parse().unwrap()
+  }
+
+  #[test]
+  fn test_network_parsing() {
+    for network in Network::iter() {
+      assert_eq!(network.to_string().to_uppercase().parse(), Ok(network));
+    }
+    assert_eq!(Network::iter().count(), 5);
+
+    let custom = Network::Custom("ci-42".to_string());
+    assert_eq!(custom.to_string(), "custom:ci-42");
+    assert_eq!("Custom:ci-42".parse(), Ok(custom.clone()));
+    let json = serde_json::to_string(&custom).unwrap();
+    assert_eq!(json, r#""custom:ci-42""#);
+    assert_eq!(serde_json::from_str::<Network>(&json).unwrap(), custom);
+
+    // serialized lowercase, the previous capitalized form is still read
+    assert_eq!(
+      serde_json::to_string(&Network::Local).unwrap(),
+      r#""local""#
+    );
+    assert_eq!(
+      serde_json::from_str::<Network>(r#""Local""#).unwrap(),
+      Network::Local
+    );
+    let enabled =
+      serde_json::from_str::<Enabled>(r#"{ "Local": true, "Mainnet": false }"#).unwrap();
+    assert_eq!(enabled.get(&Network::Local), Some(&true));
+    assert_eq!(enabled.get(&Network::Mainnet), Some(&false));
+
+    for invalid in ["moonnet", "custom:", "ci-42"] {
+      assert_eq!(
+        invalid.parse::<Network>().unwrap_err().to_string(),
+        format!(
+          "invalid network `{}`, expected one of: local, devnet, staging, testnet, mainnet, custom:<name>",
+          invalid
+        )
+      );
+    }
+  }
+
+  #[test]
+  fn test_macro_networks() {
+    // the `#[assets]` macro validates `#[asset::network(..)]` against its own list
+    let networks: Vec<String> = Network::iter().map(|network| network.to_string()).collect();
+    assert_eq!(networks, tidefi_primitives_macro::__networks!());
+  }
+
+  #[test]
+  fn test_custom_network() {
+    let ci = Network::Custom("ci".to_string());
+    assert_eq!(ci.ss58_prefix(), SUBSTRATE_SS58_PREFIX);
+    assert_eq!(ci.bitcoin_network(), BitcoinNetwork::Regtest);
+    assert_eq!(Asset::Ethereum.chain_id_on(&ci), None);
+
+    let mut registry = NetworkRegistry::builtin();
+    registry
+      .overlay_toml(
+        r#"
+[ETH."custom:ci"]
+chainId = 31337
+router = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+
+[USDT."custom:ci"]
+enabled = false
+"#,
+      )
+      .unwrap();
+    assert_eq!(registry.chain_id(&Asset::Ethereum, &ci), Some(31337));
+    assert_eq!(
+      registry.router(&Asset::Ethereum, &ci),
+      Some(address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"))
+    );
+    assert!(registry.enabled(&Asset::Ethereum, &ci));
+    assert!(!registry.enabled(&Asset::Tether, &ci));
+    assert!(!registry.enabled(&Asset::Bitcoin, &ci));
+    assert!(!registry.enabled(&Asset::Ethereum, &Network::Custom("other".to_string())));
+
+    registry
+      .overlay_vars([
+        (
+          "TIDEFI_CUSTOM_CI_ETH_MULTISIG".to_string(),
+          "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB".to_string(),
+        ),
+        (
+          "TIDEFI_CUSTOM_CI_USDT_ENABLED".to_string(),
+          "true".to_string(),
+        ),
+      ])
+      .unwrap();
+    assert_eq!(
+      registry.multisig(&Asset::Ethereum, &ci),
+      Some(address("0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB"))
+    );
+    assert!(registry.enabled(&Asset::Tether, &ci));
+    for name in ["TIDEFI_CUSTOM_CI", "TIDEFI_CUSTOM__ETH_ROUTER"] {
+      assert!(matches!(
+        registry.overlay_vars([(name.to_string(), "true".to_string())]),
+        Err(RegistryError::InvalidVariable { .. })
+      ));
+    }
+  }
+
+  #[test]
+  fn test_ss58() {
+    let account = AccountId::from([1; 32]);
+    let address = Network::Mainnet.encode_account(&account);
+    assert_eq!(
+      Network::Mainnet.decode_account(&address),
+      Ok(account.clone())
+    );
+    assert_eq!(
+      Network::Testnet.decode_account(&address),
+      Err(Ss58Error::UnexpectedPrefix {
+        expected: 42,
+        found: 7007
+      })
+    );
+
+    // `//Alice` on generic Substrate chains
+    let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    let account = Network::Local.decode_account(alice).unwrap();
+    assert_eq!(Network::Devnet.encode_account(&account), alice);
+    assert_ne!(Network::Mainnet.encode_account(&account), alice);
+    assert_eq!(
+      Network::Local.decode_account(&alice[1..]),
+      Err(Ss58Error::Invalid)
+    );
+  }
+
+  #[test]
+  fn test_schedule() {
+    let listing = Schedule {
+      enabled_from: Some(SchedulePoint::Block(100)),
+      disabled_at: Some(SchedulePoint::Block(200)),
+    };
+    assert_eq!(listing.is_active_at(SchedulePoint::Block(99)), Some(false));
+    assert_eq!(listing.is_active_at(SchedulePoint::Block(100)), Some(true));
+    assert_eq!(listing.is_active_at(SchedulePoint::Block(199)), Some(true));
+    assert_eq!(listing.is_active_at(SchedulePoint::Block(200)), Some(false));
+    assert_eq!(listing.is_active_at(SchedulePoint::Timestamp(150)), None);
+
+    let delisting = Schedule {
+      enabled_from: None,
+      disabled_at: Some(SchedulePoint::Timestamp(1_700_000_000_000)),
+    };
+    assert_eq!(
+      delisting.is_active_at(SchedulePoint::Timestamp(0)),
+      Some(true)
+    );
+    assert_eq!(
+      delisting.is_active_at(SchedulePoint::Timestamp(1_700_000_000_000)),
+      Some(false)
+    );
+    assert_eq!(delisting.is_active_at(SchedulePoint::Block(1)), None);
+    assert_eq!(
+      Schedule::default().is_active_at(SchedulePoint::Block(0)),
+      Some(true)
+    );
+
+    assert_eq!(
+      serde_json::to_string(&delisting).unwrap(),
+      r#"{"disabledAt":{"timestamp":1700000000000}}"#
+    );
+  }
+
+  #[test]
+  fn test_registry_schedule() {
+    let mut registry = NetworkRegistry::builtin();
+    let mainnet = Network::Mainnet;
+    assert_eq!(
+      registry.is_enabled_at(&Asset::USDCoin, &mainnet, SchedulePoint::Block(0)),
+      Asset::USDCoin.is_enabled_at(&mainnet, SchedulePoint::Block(0))
+    );
+
+    registry
+      .overlay_json(r#"{ "USDC": { "mainnet": { "enabledFrom": { "block": 100 } } } }"#)
+      .unwrap();
+    registry
+      .overlay_toml("[USDC.testnet]\ndisabledAt = { timestamp = 1700000000000 }\n")
+      .unwrap();
+    let ci = Network::Custom("ci".to_string());
+    registry
+      .overlay_vars([(
+        "TIDEFI_CUSTOM_CI_ETH_ENABLED_FROM".to_string(),
+        "timestamp(5)".to_string(),
+      )])
+      .unwrap();
+
+    let usdc = |network, at| registry.is_enabled_at(&Asset::USDCoin, network, at);
+    assert_eq!(usdc(&mainnet, SchedulePoint::Block(99)), Some(false));
+    assert_eq!(usdc(&mainnet, SchedulePoint::Block(100)), Some(true));
+    assert_eq!(usdc(&mainnet, SchedulePoint::Timestamp(100)), None);
+    assert_eq!(
+      usdc(
+        &Network::Testnet,
+        SchedulePoint::Timestamp(1_700_000_000_000)
+      ),
+      Some(false)
+    );
+    assert_eq!(
+      registry.is_enabled_at(&Asset::Ethereum, &ci, SchedulePoint::Timestamp(5)),
+      Some(true)
+    );
+    assert_eq!(
+      registry
+        .deployment(&Asset::Ethereum, &ci)
+        .unwrap()
+        .schedule(),
+      Schedule {
+        enabled_from: Some(SchedulePoint::Timestamp(5)),
+        disabled_at: None,
+      }
+    );
+    // not declared
+    assert_eq!(
+      registry.is_enabled_at(&Asset::Tether, &ci, SchedulePoint::Timestamp(5)),
+      Some(false)
+    );
+    assert!(registry.validate().is_ok());
+
+    registry
+      .overlay_vars([(
+        "TIDEFI_MAINNET_USDC_DISABLED_AT".to_string(),
+        "block(50)".to_string(),
+      )])
+      .unwrap();
+    assert_eq!(
+      registry.validate().unwrap_err().to_string(),
+      "USDC on mainnet must be enabled before being disabled, at blocks or timestamps"
+    );
+    assert!(matches!(
+      registry.overlay_vars([(
+        "TIDEFI_MAINNET_USDC_DISABLED_AT".to_string(),
+        "50".to_string(),
+      )]),
+      Err(RegistryError::InvalidVariable { .. })
+    ));
+
+    for point in [
+      SchedulePoint::Block(7),
+      SchedulePoint::Timestamp(1_700_000_000_000),
+    ] {
+      assert_eq!(point.to_string().parse(), Ok(point));
+    }
+    for invalid in ["block", "block()", "height(1)", "timestamp(-1)"] {
+      assert!(invalid.parse::<SchedulePoint>().is_err());
+    }
+  }
+
+  #[test]
+  fn test_bitcoin_network() {
+    assert_eq!(Network::Mainnet.bitcoin_network(), BitcoinNetwork::Mainnet);
+    assert_eq!(Network::Local.bitcoin_network().bech32_hrp(), "bcrt");
+    assert_eq!(
+      Network::Testnet.bitcoin_network().p2pkh_version(),
+      BitcoinNetwork::Signet.p2pkh_version()
+    );
+    assert!(Network::iter()
+      .all(|network| network.bitcoin_confirmations() <= Network::Mainnet.bitcoin_confirmations()));
+    assert_eq!(
+      serde_json::to_string(&BitcoinNetwork::Regtest).unwrap(),
+      r#""regtest""#
+    );
+  }
+
+  #[test]
+  fn test_network_metadata() {
+    for network in Network::iter() {
+      assert_eq!(
+        network.rpc_endpoints().map(<[_]>::len),
+        network.ws_endpoints().map(<[_]>::len)
+      );
+    }
+    assert_eq!(Network::Local.genesis_hash(), None);
+    assert_eq!(
+      Network::Local.ws_endpoints(),
+      Some(&["ws://127.0.0.1:9944"][..])
+    );
+    assert_eq!(Network::Mainnet.rpc_endpoints(), None);
+    assert_eq!(
+      Network::Local.explorer_url(42).as_deref(),
+      Some("https://polkadot.js.org/apps/?rpc=ws%3A%2F%2F127.0.0.1%3A9944#/explorer/query/42")
+    );
+    assert_eq!(Network::Mainnet.explorer_url(42), None);
+    assert_eq!(Network::Mainnet.ss58_prefix(), TIDEFI_SS58_PREFIX);
+    for network in [
+      Network::Local,
+      Network::Devnet,
+      Network::Staging,
+      Network::Testnet,
+    ] {
+      assert_eq!(network.ss58_prefix(), SUBSTRATE_SS58_PREFIX);
+    }
+  }
+
+  #[test]
+  fn test_builtin_registry() {
+    let registry = NetworkRegistry::builtin();
+    for asset in Asset::iter() {
+      for network in Network::iter() {
+        let lookup = |map: Option<Addresses>| map.and_then(|map| map.get(&network).copied());
+        assert_eq!(
+          registry.chain_id(&asset, &network),
+          asset.chain_id_on(&network)
+        );
+        assert_eq!(registry.router(&asset, &network), lookup(asset.router()));
+        assert_eq!(
+          registry.multisig(&asset, &network),
+          lookup(asset.multisig())
+        );
+        assert_eq!(registry.address(&asset, &network), lookup(asset.address()));
+        assert_eq!(
+          registry.enabled(&asset, &network),
+          asset.enabled_on(&network)
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_overlay() {
+    let mut registry = NetworkRegistry::builtin();
+    registry
+      .overlay_json(r#"{ "eth": { "local": { "chainId": 31337, "router": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed" } } }"#)
+      .unwrap();
+    registry
+      .overlay_toml("[ATH.devnet]\naddress = \"0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359\"\n\n[ATH.staging]\nenabled = false\n")
+      .unwrap();
+    registry
+      .overlay_vars([
+        ("PATH".to_string(), "/bin".to_string()),
+        ("TIDEFI_LOG".to_string(), "debug".to_string()),
+        (
+          "TIDEFI_LOCAL_ETH_MULTISIG".to_string(),
+          "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB".to_string(),
+        ),
+        (
+          "TIDEFI_MAINNET_USDT_ENABLED".to_string(),
+          "true".to_string(),
+        ),
+      ])
+      .unwrap();
+
+    let local = Network::Local;
+    assert_eq!(registry.chain_id(&Asset::Ethereum, &local), Some(31337));
+    assert_eq!(
+      registry.router(&Asset::Ethereum, &local),
+      Some(address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"))
+    );
+    assert_eq!(
+      registry.multisig(&Asset::Ethereum, &local),
+      Some(address("0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB"))
+    );
+    assert_eq!(
+      registry.address(&Asset::AllTimeHigh, &Network::Devnet),
+      Some(address("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"))
+    );
+    assert!(!registry.enabled(&Asset::AllTimeHigh, &Network::Staging));
+    assert!(registry.enabled(&Asset::Tether, &Network::Mainnet));
+    // untouched
+    assert_eq!(
+      registry.router(&Asset::Ethereum, &Network::Mainnet),
+      Some(address("0x8f4b7bef83d6e2ef0d8bb23db8dbf7f9f2c69729"))
+    );
+    assert_eq!(registry.validate().unwrap(), vec![]);
+
+    assert!(matches!(
+      registry.overlay_json(r#"{ "DOGE": { "local": {} } }"#),
+      Err(RegistryError::UnknownAsset(_))
+    ));
+    assert!(matches!(
+      registry.overlay_json(r#"{ "ETH": { "moon": {} } }"#),
+      Err(RegistryError::UnknownNetwork(_))
+    ));
+    assert!(matches!(
+      registry.overlay_json(r#"{ "ETH": { "local": { "routr": "0x01" } } }"#),
+      Err(RegistryError::Format(_))
+    ));
+    assert!(matches!(
+      registry.overlay_vars([("TIDEFI_LOCAL_ETH_CHAIN_ID".to_string(), "one".to_string())]),
+      Err(RegistryError::InvalidVariable { .. })
+    ));
+    assert!(matches!(
+      registry.overlay_json(
+        r#"{ "ETH": { "local": { "router": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD" } } }"#
+      ),
+      Err(RegistryError::Format(_))
+    ));
+    assert!(matches!(
+      registry.overlay_vars([("TIDEFI_LOCAL_ETH_ROUTER".to_string(), "0x01".to_string())]),
+      Err(RegistryError::InvalidVariable { .. })
+    ));
+    assert!(matches!(
+      registry.overlay_vars([("TIDEFI_LOCAL_ETH_TOKEN".to_string(), "0x01".to_string())]),
+      Err(RegistryError::InvalidVariable { .. })
+    ));
+  }
+
+  #[test]
+  fn test_validate() {
+    let warnings = |registry: &NetworkRegistry| {
+      registry
+        .validate()
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+    };
+    let mut registry = NetworkRegistry::builtin();
+    // ATH isn't deployed on devnet and staging yet
+    assert_eq!(
+      warnings(&registry),
+      [
+        "ATH is enabled on devnet without an address",
+        "ATH is enabled on staging without an address"
+      ]
+    );
+    registry
+      .overlay_json(
+        r#"{ "ATH": { "devnet": { "address": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed" } } }"#,
+      )
+      .unwrap();
+    assert_eq!(
+      warnings(&registry),
+      ["ATH is enabled on staging without an address"]
+    );
+    registry
+      .overlay_toml("[ATH.staging]\nenabled = false\n")
+      .unwrap();
+    assert_eq!(registry.validate().unwrap(), vec![]);
+
+    // native and layer one assets don't need an address
+    assert!(registry
+      .address(&Asset::Bitcoin, &Network::Mainnet)
+      .is_none());
+  }
+
+  #[test]
+  fn test_overlay_file() {
+    // unique per process, test runs may be concurrent
+    let path = std::env::temp_dir().join(format!(
+      "tidefi-network-registry-{}.toml",
+      std::process::id()
+    ));
+    std::fs::write(&path, "[ETH.local]\nchain_id = 31337\n").unwrap();
+    let mut registry = NetworkRegistry::builtin();
+    registry.overlay_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+      registry.chain_id(&Asset::Ethereum, &Network::Local),
+      Some(31337)
+    );
+
+    assert!(matches!(
+      registry.overlay_file("networks.yaml"),
+      Err(RegistryError::UnsupportedFormat(path)) if path == "networks.yaml"
+    ));
+    assert!(matches!(
+      registry.overlay_file("missing-networks.json"),
+      Err(RegistryError::Io(_))
+    ));
+  }
 }
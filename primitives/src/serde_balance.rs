@@ -0,0 +1,290 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of tidefi-primitives.
+
+// tidefi-primitives is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// tidefi-primitives is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with tidefi-primitives.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Serialize balances as strings, JavaScript numbers can't represent a `u128` without loss.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Transfer {
+//!   #[serde(with = "tidefi_primitives::serde_balance")]
+//!   amount: Balance,
+//!   #[serde(with = "tidefi_primitives::serde_balance::option")]
+//!   fee: Option<Balance>,
+//!   #[serde(with = "tidefi_primitives::serde_balance::decimal")]
+//!   total: AssetAmount,
+//! }
+//! ```
+//!
+//! Every `Balance` field of the crate is serialized this way with the `balance-as-string` feature.
+//!
+//! Human-readable formats deserialize balances from strings or numbers. `serde_json` hands
+//! integers above `u64::MAX` over as lossy floats, which are rejected, unless its
+//! `arbitrary_precision` feature is enabled, as `balance-as-string` does: numbers then cover the
+//! whole `u128` range. Compact formats, e.g. bincode, can't tell strings and numbers apart, and
+//! only read the strings they were serialized to.
+
+use core::{fmt, marker::PhantomData, str::FromStr};
+use serde::{de, Deserializer, Serializer};
+
+/// Serialize a balance as a string of base units, e.g.: `"1500000"`.
+pub fn serialize<S: Serializer, T: fmt::Display>(
+  balance: &T,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(balance)
+}
+
+/// Deserialize a balance from a string of base units, or a number in human-readable formats.
+pub fn deserialize<'de, D: Deserializer<'de>, T: FromStr>(deserializer: D) -> Result<T, D::Error> {
+  if deserializer.is_human_readable() {
+    deserializer.deserialize_any(BalanceVisitor(PhantomData))
+  } else {
+    deserializer.deserialize_str(BalanceVisitor(PhantomData))
+  }
+}
+
+/// Key of the map `serde_json` hands numbers over as, with its `arbitrary_precision` feature.
+const JSON_NUMBER: &str = "$serde_json::private::Number";
+
+struct BalanceVisitor<T>(PhantomData<T>);
+
+impl<'de, T: FromStr> de::Visitor<'de> for BalanceVisitor<T> {
+  type Value = T;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "a balance as a string or an unsigned integer")
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+    value
+      .parse()
+      .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+    self.visit_str(&value.to_string())
+  }
+
+  fn visit_u128<E: de::Error>(self, value: u128) -> Result<T, E> {
+    self.visit_str(&value.to_string())
+  }
+
+  fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+    match map.next_key::<String>()?.as_deref() {
+      Some(JSON_NUMBER) => {
+        let number = map.next_value::<String>()?;
+        number
+          .parse()
+          .map_err(|_| de::Error::invalid_value(de::Unexpected::Other(&number), &self))
+      }
+      _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+    }
+  }
+}
+
+/// Serialize an optional balance as a string, or `null`.
+pub mod option {
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  #[derive(Deserialize)]
+  struct Balance<T: core::str::FromStr>(#[serde(with = "super")] T);
+
+  /// Serialize an optional balance as a string of base units, or `null`.
+  pub fn serialize<S: Serializer, T: core::fmt::Display>(
+    balance: &Option<T>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match balance {
+      Some(balance) => serializer.collect_str(balance),
+      None => serializer.serialize_none(),
+    }
+  }
+
+  /// Deserialize an optional balance from `null`, a string or a number of base units.
+  pub fn deserialize<'de, D: Deserializer<'de>, T: core::str::FromStr>(
+    deserializer: D,
+  ) -> Result<Option<T>, D::Error> {
+    Ok(Option::<Balance<T>>::deserialize(deserializer)?.map(|Balance(balance)| balance))
+  }
+}
+
+/// Serialize an `AssetAmount` with a decimal amount of its currency, e.g.:
+/// `{ "currency": { "Wrapped": 2 }, "amount": "0.015" }` for `1_500_000` satoshis.
+pub mod decimal {
+  use crate::{amount::AssetAmount, assets::Asset, CurrencyId};
+  use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+  #[derive(Serialize, Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct Decimal<T> {
+    currency: CurrencyId,
+    amount: T,
+  }
+
+  /// Serialize the amount in units of its currency, fails if the currency isn't a known asset.
+  pub fn serialize<S: Serializer>(
+    asset_amount: &AssetAmount,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let asset = Asset::try_from(asset_amount.currency).map_err(ser::Error::custom)?;
+    Decimal {
+      currency: asset_amount.currency,
+      amount: asset.format_amount(asset_amount.amount, Default::default()),
+    }
+    .serialize(serializer)
+  }
+
+  /// Deserialize a decimal amount, optionally suffixed by a denomination, e.g.: `"1.5 TDFY"`.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AssetAmount, D::Error> {
+    let Decimal::<String> { currency, amount } = Decimal::deserialize(deserializer)?;
+    let asset = Asset::try_from(currency).map_err(de::Error::custom)?;
+    let amount = asset.parse_amount(&amount).map_err(de::Error::custom)?;
+    Ok(AssetAmount::new(currency, amount))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{amount::AssetAmount, Balance, BalanceInfo, CurrencyId};
+  use serde::{Deserialize, Serialize};
+  use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+  #[derive(Serialize, Deserialize, Debug, PartialEq)]
+  struct Transfer {
+    #[serde(with = "super")]
+    amount: Balance,
+    #[serde(with = "super::option")]
+    fee: Option<Balance>,
+    #[serde(with = "super::decimal")]
+    total: AssetAmount,
+  }
+
+  #[test]
+  fn test_serde_balance() {
+    let transfer = Transfer {
+      amount: Balance::MAX,
+      fee: Some(1),
+      total: AssetAmount::new(CurrencyId::Wrapped(2), 1_500_000),
+    };
+    let json = serde_json::to_string(&transfer).unwrap();
+    assert_eq!(
+      json,
+      r#"{"amount":"340282366920938463463374607431768211455","fee":"1","total":{"currency":{"Wrapped":2},"amount":"0.015"}}"#
+    );
+    assert_eq!(serde_json::from_str::<Transfer>(&json).unwrap(), transfer);
+
+    // numbers are still accepted
+    let transfer = serde_json::from_str::<Transfer>(
+      r#"{"amount":42,"fee":null,"total":{"currency":"Tdfy","amount":"1.5 TDFY"}}"#,
+    )
+    .unwrap();
+    assert_eq!(transfer.amount, 42);
+    assert_eq!(transfer.fee, None);
+    assert_eq!(
+      transfer.total,
+      AssetAmount::new(CurrencyId::Tdfy, 1_500_000_000_000)
+    );
+
+    assert!(serde_json::from_str::<Transfer>(
+      r#"{"amount":"-1","fee":null,"total":{"currency":"Tdfy","amount":"1"}}"#
+    )
+    .is_err());
+    assert!(serde_json::from_str::<Transfer>(
+      r#"{"amount":"1","fee":null,"total":{"currency":{"Wrapped":42},"amount":"1"}}"#
+    )
+    .is_err());
+
+    let transfer = serde_json::from_str::<Transfer>(&format!(
+      r#"{{"amount":{},"fee":null,"total":{{"currency":"Tdfy","amount":"1"}}}}"#,
+      u64::MAX
+    ))
+    .unwrap();
+    assert_eq!(transfer.amount, u64::MAX.into());
+    let transfer = serde_json::from_str::<Transfer>(&format!(
+      r#"{{"amount":{},"fee":null,"total":{{"currency":"Tdfy","amount":"1"}}}}"#,
+      Balance::MAX
+    ));
+    if cfg!(feature = "balance-as-string") {
+      assert_eq!(transfer.unwrap().amount, Balance::MAX);
+    } else {
+      // parsed as a float without `arbitrary_precision`, which would lose precision
+      assert!(transfer
+        .unwrap_err()
+        .to_string()
+        .starts_with("invalid type: floating point"));
+    }
+
+    let balance = serde_json::to_string(&BalanceInfo { amount: 7 }).unwrap();
+    assert_eq!(balance, r#"{"amount":"7"}"#);
+  }
+
+  #[test]
+  fn test_compact_balance() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Amount(#[serde(with = "super")] Balance);
+
+    // compact formats only have the string balances are serialized to
+    assert_tokens(
+      &Amount(Balance::MAX).compact(),
+      &[
+        Token::NewtypeStruct { name: "Amount" },
+        Token::Str("340282366920938463463374607431768211455"),
+      ],
+    );
+    assert_de_tokens(
+      &Amount(42).readable(),
+      &[Token::NewtypeStruct { name: "Amount" }, Token::U64(42)],
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "balance-as-string")]
+  fn test_balance_as_string() {
+    let fee = crate::Fee {
+      amount: Balance::MAX,
+      fee: 2,
+      fee_tdfy: 3,
+    };
+    let json = serde_json::to_string(&fee).unwrap();
+    assert_eq!(
+      json,
+      r#"{"amount":"340282366920938463463374607431768211455","fee":"2","feeTdfy":"3"}"#
+    );
+    assert_eq!(serde_json::from_str::<crate::Fee>(&json).unwrap(), fee);
+
+    let balance = crate::CurrencyBalance {
+      available: 1_u128,
+      reserved: 2_u128,
+    };
+    assert_eq!(
+      serde_json::to_string(&balance).unwrap(),
+      r#"{"available":"1","reserved":"2"}"#
+    );
+
+    // `gasAmount` is optional
+    let mint = serde_json::from_str::<crate::Mint<String, String>>(
+      r#"{"accountId":"alice","currencyId":"Tdfy","mintAmount":"1","transactionId":"0x01","complianceLevel":"green"}"#,
+    )
+    .unwrap();
+    assert_eq!(mint.mint_amount, 1);
+    assert_eq!(mint.gas_amount, None);
+    let mint = serde_json::from_str::<crate::Mint<String, String>>(
+      r#"{"accountId":"alice","currencyId":"Tdfy","mintAmount":"1","gasAmount":2,"transactionId":"0x01","complianceLevel":"green"}"#,
+    )
+    .unwrap();
+    assert_eq!(mint.gas_amount, Some(2));
+  }
+}
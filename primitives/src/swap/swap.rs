@@ -19,7 +19,7 @@ use crate::{
   MaxEncodedLen, Permill, TypeInfo,
 };
 use codec::alloc::string::String;
-use sp_arithmetic::{traits::CheckedDiv, FixedPointNumber};
+use sp_arithmetic::traits::CheckedDiv;
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -60,9 +60,11 @@ pub struct SwapConfirmation {
   /// Request ID of the market maker swap request, used to fulfill this swap request.
   pub request_id: Hash,
   /// Amount of the source, should be formatted with the source currency, the market maker will receive this amount of asset.
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_to_receive: Balance,
   /// Amount of the destination, should be formatted with the destination currency, the market maker will send this amount of asset,
   /// and the swap will be filled with this amount. It may provide a partial or a complete fill.
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_to_send: Balance,
 }
 
@@ -74,6 +76,7 @@ pub struct SwapExtrinsic {
   /// Signed extrinsic
   pub extrinsic: String,
   /// The estimated swap fee
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub swap_fee: Balance,
   /// The currency the swap fees are taken
   pub swap_fee_currency: CurrencyId,
@@ -93,14 +96,18 @@ pub struct Swap<AccountId, BlockNumber> {
   /// Asset ID of the swap.
   pub token_from: CurrencyId,
   /// Amount from
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_from: Balance,
   /// Amount from (currently filled -- if partial)
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_from_filled: Balance,
   /// Asset ID to the swap.
   pub token_to: CurrencyId,
   /// Amount to (requested)
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_to: Balance,
   /// Amount to (currently filled -- if partial)
+  #[cfg_attr(feature = "balance-as-string", serde(with = "crate::serde_balance"))]
   pub amount_to_filled: Balance,
   /// Swap status
   pub status: SwapStatus,
@@ -146,18 +153,17 @@ impl<AccountId: Clone, BlockNumber: Clone> Swap<AccountId, BlockNumber> {
   ) -> Result<FixedU128, SlippageError> {
     if self.token_from == market_pair.base_asset {
       // selling
-      return Ok(self.pay_per_token(
-        |base_amount| base_amount,
+      return self.pay_per_token(
+        Ok,
         |quote_amount| {
           quote_amount
             .checked_sub(self.slippage * quote_amount)
             .ok_or(SlippageError::ArithmeticError)
-            .unwrap_or(quote_amount)
         },
         market_pair,
         self.amount_from,
         self.amount_to,
-      )?);
+      );
     }
 
     Err(SlippageError::NoLowerBoundForBuyingPrice)
@@ -169,18 +175,17 @@ impl<AccountId: Clone, BlockNumber: Clone> Swap<AccountId, BlockNumber> {
   ) -> Result<FixedU128, SlippageError> {
     if self.token_to == market_pair.base_asset {
       // buying
-      return Ok(self.pay_per_token(
-        |base_amount| base_amount,
+      return self.pay_per_token(
+        Ok,
         |quote_amount| {
           quote_amount
             .checked_add(self.slippage * quote_amount)
             .ok_or(SlippageError::ArithmeticError)
-            .unwrap_or(quote_amount)
         },
         market_pair,
         self.amount_to,
         self.amount_from,
-      )?);
+      );
     }
 
     Err(SlippageError::NoUpperBoundForSellingPrice)
@@ -195,27 +200,27 @@ impl<AccountId: Clone, BlockNumber: Clone> Swap<AccountId, BlockNumber> {
     quote_amount: Balance,
   ) -> Result<FixedU128, SlippageError>
   where
-    FT: Fn(Balance) -> Balance,
-    FF: Fn(Balance) -> Balance,
+    FT: Fn(Balance) -> Result<Balance, SlippageError>,
+    FF: Fn(Balance) -> Result<Balance, SlippageError>,
   {
     let base_asset: Asset = market_pair
       .base_asset
       .try_into()
       .map_err(|_| SlippageError::UnknownAsset)?;
 
-    let base_token_one_unit = base_asset.saturating_mul(1);
-
     let quote_asset: Asset = market_pair
       .quote_asset
       .try_into()
       .map_err(|_| SlippageError::UnknownAsset)?;
-    let quote_token_one_unit = quote_asset.saturating_mul(1);
 
-    FixedU128::saturating_from_rational(quote_amount_closure(quote_amount), quote_token_one_unit)
-      .checked_div(&FixedU128::saturating_from_rational(
-        base_amount_closure(base_amount),
-        base_token_one_unit,
-      ))
+    quote_asset
+      .checked_to_fixed(quote_amount_closure(quote_amount)?)
+      .map_err(|_| SlippageError::ArithmeticError)?
+      .checked_div(
+        &base_asset
+          .checked_to_fixed(base_amount_closure(base_amount)?)
+          .map_err(|_| SlippageError::ArithmeticError)?,
+      )
       .ok_or(SlippageError::SlippageOverflow)
   }
 
@@ -266,21 +271,21 @@ impl<AccountId: Clone, BlockNumber: Clone> Swap<AccountId, BlockNumber> {
       .base_asset
       .try_into()
       .map_err(|_| SlippageError::UnknownAsset)?;
-    let base_asset_one_unit = base_asset.saturating_mul(1);
 
     let quote_asset: Asset = market_pair
       .quote_asset
       .try_into()
       .map_err(|_| SlippageError::UnknownAsset)?;
-    let quote_asset_one_unit = quote_asset.saturating_mul(1);
 
-    let price_offered =
-      FixedU128::saturating_from_rational(offered_quote_amount, quote_asset_one_unit)
-        .checked_div(&FixedU128::saturating_from_rational(
-          offered_base_amount,
-          base_asset_one_unit,
-        ))
-        .ok_or(SlippageError::SlippageOverflow)?;
+    let price_offered = quote_asset
+      .checked_to_fixed(offered_quote_amount)
+      .map_err(|_| SlippageError::ArithmeticError)?
+      .checked_div(
+        &base_asset
+          .checked_to_fixed(offered_base_amount)
+          .map_err(|_| SlippageError::ArithmeticError)?,
+      )
+      .ok_or(SlippageError::SlippageOverflow)?;
 
     self.validate_slippage_dry_run(price_offered, market_pair)?;
 
@@ -379,4 +379,39 @@ mod tests {
       quote_asset: Asset::USDCoin.currency_id(),
     }
   );
+
+  #[test]
+  fn slippage_overflow_is_an_arithmetic_error() {
+    let market_pair = MarketPair {
+      base_asset: Asset::Tdfy.currency_id(),
+      quote_asset: Asset::USDCoin.currency_id(),
+    };
+    let one_percent = Permill::from_rational(1_u128, 100_u128);
+
+    // buying TDFY, the upper bound of the price overflows with the slippage
+    let swap = build_test_swap(
+      AccountId::from_str(BOB).unwrap(),
+      SwapType::Market,
+      Asset::USDCoin.currency_id(),
+      Balance::MAX,
+      Asset::Tdfy.currency_id(),
+      Asset::Tdfy.saturating_mul(1_000),
+      one_percent,
+    );
+    assert_eq!(
+      swap.pay_per_token_upper_bond(&market_pair),
+      Err(SlippageError::ArithmeticError)
+    );
+
+    // the offered amount of USDC can't be represented as a price
+    assert_eq!(
+      swap.validate_slippage(
+        &swap,
+        Asset::Tdfy.saturating_mul(1_000),
+        Balance::MAX,
+        &market_pair
+      ),
+      Err(SlippageError::ArithmeticError)
+    );
+  }
 }
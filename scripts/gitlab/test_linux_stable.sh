@@ -2,3 +2,6 @@
 set -eux
 
 time cargo test --all-targets --workspace
+time cargo test --all-targets -p tidefi-primitives --features balance-as-string
+# the runtime builds without `std`, substrate primitives only build for wasm in this case
+time cargo check -p tidefi-primitives --no-default-features --target wasm32-unknown-unknown